@@ -21,6 +21,7 @@ async function run() {
    *
    * const db = new Glue("localstorage", "database-name");
    * const db = new Glue("sessionstorage", "database-name");
+   * const db = new Glue("indexeddb", "database-name");
    */
 
   const stringify = v => JSON.stringify(v, null, ' ');
@@ -0,0 +1,78 @@
+use async_trait::async_trait;
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+use gluesql_core::{Error, MutResult, Result, Row, Schema};
+
+use crate::catalog::Catalog;
+
+/// Version of the document written by `Glue::export_json`.
+pub const BACKUP_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum BackupError {
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for BackupError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            BackupError::UnsupportedVersion(v) => write!(f, "unsupported backup version: {}", v),
+        }
+    }
+}
+
+impl std::error::Error for BackupError {}
+
+impl From<BackupError> for Error {
+    fn from(e: BackupError) -> Self {
+        Error::Storage(Box::new(e))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct Backup {
+    pub version: u32,
+    pub tables: Vec<TableBackup>,
+}
+
+#[derive(Serialize, Deserialize)]
+pub struct TableBackup {
+    pub schema: Schema,
+    /// Last id generated for the table.
+    pub id: u64,
+    pub rows: Vec<(u64, Row)>,
+}
+
+pub fn to_json(tables: Vec<TableBackup>) -> Result<String> {
+    let backup = Backup {
+        version: BACKUP_VERSION,
+        tables,
+    };
+
+    serde_json::to_string(&backup).map_err(|e| Error::Storage(Box::new(e)))
+}
+
+pub fn from_json(backup: &str) -> Result<Vec<TableBackup>> {
+    let Backup { version, tables } =
+        serde_json::from_str(backup).map_err(|e| Error::Storage(Box::new(e)))?;
+
+    match version {
+        BACKUP_VERSION => Ok(tables),
+        _ => Err(BackupError::UnsupportedVersion(version).into()),
+    }
+}
+
+/// Copies whole tables in and out of a storage, keeping row ids and id counters.
+#[async_trait(?Send)]
+pub trait Backupable
+where
+    Self: Catalog + Sized,
+{
+    /// Every table, sorted by table name.
+    async fn export_tables(&self) -> Result<Vec<TableBackup>>;
+
+    /// Replaces the tables of the same names and keeps the others.
+    /// Nothing is written unless every table is imported.
+    async fn import_tables(self, tables: Vec<TableBackup>) -> MutResult<Self, ()>;
+}
@@ -0,0 +1,10 @@
+use async_trait::async_trait;
+
+use gluesql_core::{Result, Schema};
+
+/// Enumerates the tables of a storage, which `Store` can only look up by name.
+#[async_trait(?Send)]
+pub trait Catalog {
+    /// Schemas of every table, sorted by table name.
+    async fn list_schemas(&self) -> Result<Vec<Schema>>;
+}
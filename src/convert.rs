@@ -1,56 +1,193 @@
-use serde_json::map::Map;
-use serde_json::value::Value as Json;
+use js_sys::{Array, Object, Reflect};
 use wasm_bindgen::prelude::JsValue;
 
-use gluesql_core::{Payload, Row, Value};
+use gluesql_core::parser::ast::{ColumnDef, ColumnOption, ColumnOptionDef, DataType};
+use gluesql_core::{Payload, Row, Schema, Value};
 
-pub fn convert(payloads: Vec<Payload>) -> JsValue {
-    let payloads = payloads
+pub enum Output {
+    Payload(Payload),
+    Select {
+        labels: Vec<String>,
+        types: Vec<Option<DataType>>,
+        rows: Vec<Row>,
+    },
+    ShowTables(Vec<Schema>),
+    Begin,
+    Commit,
+    Rollback,
+}
+
+pub fn convert(outputs: Vec<Output>, object_rows: bool) -> JsValue {
+    outputs
         .into_iter()
-        .map(convert_payload)
-        .map(|(query, data)| {
-            let mut map = Map::new();
+        .map(|output| convert_output(output, object_rows))
+        .collect::<Array>()
+        .into()
+}
 
-            map.insert("query".to_owned(), Json::String(query));
-            map.insert("data".to_owned(), data);
+fn set(object: &Object, key: &str, value: &JsValue) {
+    Reflect::set(object, &JsValue::from_str(key), value).unwrap();
+}
 
-            Json::Object(map)
-        })
-        .collect();
-    let payloads = Json::Array(payloads);
+fn convert_output(output: Output, object_rows: bool) -> JsValue {
+    let mut columns = None;
+
+    let (query, data) = match output {
+        Output::Payload(payload) => convert_payload(payload),
+        Output::Select {
+            labels,
+            types,
+            rows,
+        } => {
+            let data = convert_rows(rows, &labels, object_rows);
+
+            columns = Some((convert_labels(&labels), convert_types(&labels, &types)));
+
+            ("SELECT", data.into())
+        }
+        Output::ShowTables(schemas) => {
+            let names = schemas
+                .iter()
+                .map(|schema| JsValue::from_str(&schema.table_name))
+                .collect::<Array>();
+
+            ("SHOW TABLES", names.into())
+        }
+        Output::Begin => ("BEGIN", JsValue::NULL),
+        Output::Commit => ("COMMIT", JsValue::NULL),
+        Output::Rollback => ("ROLLBACK", JsValue::NULL),
+    };
+
+    let object = Object::new();
+    set(&object, "query", &JsValue::from_str(query));
 
-    JsValue::from_serde(&payloads).unwrap()
+    if let Some((labels, types)) = columns {
+        set(&object, "labels", &labels);
+        set(&object, "types", &types);
+    }
+
+    set(&object, "data", &data);
+
+    object.into()
 }
 
-fn convert_payload(payload: Payload) -> (String, Json) {
+fn convert_payload(payload: Payload) -> (&'static str, JsValue) {
     match payload {
-        Payload::Create => ("CREATE".to_owned(), Json::Null),
-        Payload::Insert(num) => ("INSERT".to_owned(), Json::from(num)),
-        Payload::Select { rows, .. } => (
-            "SELECT".to_owned(),
-            Json::Array(rows.into_iter().map(convert_row).collect()),
-        ),
-        Payload::Delete(num) => ("DELETE".to_owned(), Json::from(num)),
-        Payload::Update(num) => ("UPDATE".to_owned(), Json::from(num)),
-        Payload::DropTable => ("DROP".to_owned(), Json::Null),
-        Payload::AlterTable => ("ALTER TABLE".to_owned(), Json::Null),
+        Payload::Create => ("CREATE", JsValue::NULL),
+        Payload::Insert(num) => ("INSERT", JsValue::from(num as f64)),
+        Payload::Select { labels, rows } => ("SELECT", convert_rows(rows, &labels, false).into()),
+        Payload::Delete(num) => ("DELETE", JsValue::from(num as f64)),
+        Payload::Update(num) => ("UPDATE", JsValue::from(num as f64)),
+        Payload::DropTable => ("DROP", JsValue::NULL),
+        Payload::AlterTable => ("ALTER TABLE", JsValue::NULL),
     }
 }
 
-fn convert_row(row: Row) -> Json {
+pub fn convert_schemas(schemas: Vec<Schema>) -> JsValue {
+    schemas
+        .into_iter()
+        .map(|schema| {
+            let columns = schema
+                .column_defs
+                .iter()
+                .map(convert_column_def)
+                .collect::<Array>();
+
+            let object = Object::new();
+            set(&object, "name", &JsValue::from_str(&schema.table_name));
+            set(&object, "columns", &columns);
+
+            object
+        })
+        .collect::<Array>()
+        .into()
+}
+
+fn convert_column_def(column_def: &ColumnDef) -> Object {
+    let ColumnDef {
+        name,
+        data_type,
+        options,
+        ..
+    } = column_def;
+
+    let nullable = options
+        .iter()
+        .any(|ColumnOptionDef { option, .. }| option == &ColumnOption::Null);
+    let default = options
+        .iter()
+        .find_map(|ColumnOptionDef { option, .. }| match option {
+            ColumnOption::Default(expr) => Some(JsValue::from_str(&expr.to_string())),
+            _ => None,
+        })
+        .unwrap_or(JsValue::NULL);
+
+    let object = Object::new();
+    set(&object, "name", &JsValue::from_str(&name.value));
+    set(&object, "type", &JsValue::from_str(&data_type.to_string()));
+    set(&object, "nullable", &JsValue::from_bool(nullable));
+    set(&object, "default", &default);
+
+    object
+}
+
+pub fn convert_labels(labels: &[String]) -> Array {
+    labels
+        .iter()
+        .map(|label| JsValue::from_str(label))
+        .collect()
+}
+
+pub fn convert_types(labels: &[String], types: &[Option<DataType>]) -> Array {
+    (0..labels.len())
+        .map(|i| match types.get(i) {
+            Some(Some(data_type)) => JsValue::from_str(&data_type.to_string()),
+            _ => JsValue::NULL,
+        })
+        .collect()
+}
+
+pub fn convert_rows<I>(rows: I, labels: &[String], object_rows: bool) -> Array
+where
+    I: IntoIterator<Item = Row>,
+{
+    rows.into_iter()
+        .map(|row| match object_rows {
+            true => convert_object_row(labels, row),
+            false => convert_row(row),
+        })
+        .collect()
+}
+
+fn convert_row(row: Row) -> JsValue {
+    let Row(values) = row;
+
+    values
+        .into_iter()
+        .map(convert_value)
+        .collect::<Array>()
+        .into()
+}
+
+fn convert_object_row(labels: &[String], row: Row) -> JsValue {
     let Row(values) = row;
+    let object = Object::new();
+
+    for (label, value) in labels.iter().zip(values) {
+        set(&object, label, &convert_value(value));
+    }
 
-    Json::Array(values.into_iter().map(convert_value).collect())
+    object.into()
 }
 
-fn convert_value(value: Value) -> Json {
+fn convert_value(value: Value) -> JsValue {
     use Value::*;
 
     match value {
-        Bool(v) | OptBool(Some(v)) => Json::Bool(v),
-        I64(v) | OptI64(Some(v)) => Json::from(v),
-        F64(v) | OptF64(Some(v)) => Json::from(v),
-        Str(v) | OptStr(Some(v)) => Json::String(v),
-        OptBool(None) | OptI64(None) | OptF64(None) | OptStr(None) | Empty => Json::Null,
+        Bool(v) | OptBool(Some(v)) => JsValue::from_bool(v),
+        I64(v) | OptI64(Some(v)) => JsValue::from_f64(v as f64),
+        F64(v) | OptF64(Some(v)) => JsValue::from_f64(v),
+        Str(v) | OptStr(Some(v)) => JsValue::from_str(&v),
+        OptBool(None) | OptI64(None) | OptF64(None) | OptStr(None) | Empty => JsValue::NULL,
     }
 }
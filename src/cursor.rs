@@ -0,0 +1,87 @@
+use js_sys::{Array, Object, Promise, Reflect};
+use std::vec::IntoIter;
+use wasm_bindgen::prelude::*;
+
+use gluesql_core::parser::ast::DataType;
+use gluesql_core::Row;
+
+use crate::convert::{convert_labels, convert_rows, convert_types};
+
+/// Async iterator over the rows of a SELECT, which yields them in batches.
+/// The whole result is still held in WebAssembly memory, only the conversion to JavaScript values
+/// happens one batch at a time.
+#[wasm_bindgen]
+pub struct Cursor {
+    labels: Vec<String>,
+    types: Vec<Option<DataType>>,
+    rows: IntoIter<Row>,
+    batch_size: usize,
+    object_rows: bool,
+}
+
+impl Cursor {
+    pub fn new(
+        labels: Vec<String>,
+        types: Vec<Option<DataType>>,
+        rows: Vec<Row>,
+        batch_size: usize,
+        object_rows: bool,
+    ) -> Self {
+        Self {
+            labels,
+            types,
+            rows: rows.into_iter(),
+            batch_size: batch_size.max(1),
+            object_rows,
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Cursor {
+    #[wasm_bindgen(getter)]
+    pub fn labels(&self) -> Array {
+        convert_labels(&self.labels)
+    }
+
+    #[wasm_bindgen(getter)]
+    pub fn types(&self) -> Array {
+        convert_types(&self.labels, &self.types)
+    }
+
+    /// Makes the cursor usable in `for await (const rows of cursor)`, by moving the rows left to
+    /// a new cursor.
+    #[wasm_bindgen(js_name = "[Symbol.asyncIterator]")]
+    pub fn async_iterator(&mut self) -> Cursor {
+        Cursor {
+            labels: self.labels.clone(),
+            types: self.types.clone(),
+            rows: std::mem::take(&mut self.rows),
+            batch_size: self.batch_size,
+            object_rows: self.object_rows,
+        }
+    }
+
+    /// Resolves to `{ done, value }`, where `value` is the next batch of rows.
+    #[wasm_bindgen(js_name = next)]
+    pub fn next_batch(&mut self) -> Promise {
+        let rows = self.rows.by_ref().take(self.batch_size);
+        let rows = convert_rows(rows, &self.labels, self.object_rows);
+        let done = rows.length() == 0;
+
+        let result = Object::new();
+        let set = |key: &str, value: &JsValue| {
+            Reflect::set(&result, &JsValue::from_str(key), value).unwrap();
+        };
+
+        let value = match done {
+            true => JsValue::UNDEFINED,
+            false => rows.into(),
+        };
+
+        set("done", &JsValue::from_bool(done));
+        set("value", &value);
+
+        Promise::resolve(&JsValue::from(result))
+    }
+}
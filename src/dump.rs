@@ -0,0 +1,95 @@
+use std::fmt::{self, Debug};
+
+use gluesql_core::{Error, Result, Row, Schema, Store, Value};
+
+use crate::catalog::Catalog;
+
+#[derive(Debug)]
+pub enum DumpError {
+    UnsupportedFloat(f64),
+}
+
+impl fmt::Display for DumpError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            DumpError::UnsupportedFloat(v) => write!(f, "float cannot be dumped as SQL: {}", v),
+        }
+    }
+}
+
+impl std::error::Error for DumpError {}
+
+impl From<DumpError> for Error {
+    fn from(e: DumpError) -> Self {
+        Error::Storage(Box::new(e))
+    }
+}
+
+/// Double quotes text literals and identifiers, escaping double quotes inside as `""`.
+fn quote(value: &str) -> String {
+    format!("\"{}\"", value.replace('"', "\"\""))
+}
+
+fn convert_value(value: &Value) -> Result<String> {
+    use Value::*;
+
+    match value {
+        Bool(v) | OptBool(Some(v)) => Ok(if *v { "TRUE" } else { "FALSE" }.to_owned()),
+        I64(v) | OptI64(Some(v)) => Ok(v.to_string()),
+        F64(v) | OptF64(Some(v)) if v.is_finite() => Ok(v.to_string()),
+        F64(v) | OptF64(Some(v)) => Err(DumpError::UnsupportedFloat(*v).into()),
+        Str(v) | OptStr(Some(v)) => Ok(quote(v)),
+        OptBool(None) | OptI64(None) | OptF64(None) | OptStr(None) | Empty => Ok("NULL".to_owned()),
+    }
+}
+
+/// Writes every table of `storage` as `CREATE TABLE` and `INSERT` statements, which rebuild
+/// it when passed to `execute`.
+pub async fn dump<T, U>(storage: &U) -> Result<String>
+where
+    T: 'static + Debug,
+    U: Store<T> + Catalog,
+{
+    let mut sql = String::new();
+
+    for Schema {
+        table_name,
+        column_defs,
+    } in storage.list_schemas().await?
+    {
+        let columns = column_defs
+            .into_iter()
+            .map(|mut column_def| {
+                if column_def.name.quote_style == Some('"') {
+                    column_def.name.value = column_def.name.value.replace('"', "\"\"");
+                }
+
+                column_def.to_string()
+            })
+            .collect::<Vec<_>>()
+            .join(", ");
+
+        sql.push_str(&format!(
+            "CREATE TABLE {} ({});\n",
+            quote(&table_name),
+            columns
+        ));
+
+        for item in storage.scan_data(&table_name).await? {
+            let (_, Row(values)) = item?;
+            let values = values
+                .iter()
+                .map(convert_value)
+                .collect::<Result<Vec<_>>>()?
+                .join(", ");
+
+            sql.push_str(&format!(
+                "INSERT INTO {} VALUES ({});\n",
+                quote(&table_name),
+                values
+            ));
+        }
+    }
+
+    Ok(sql)
+}
@@ -0,0 +1,135 @@
+use js_sys::Reflect;
+use wasm_bindgen::prelude::JsValue;
+
+use gluesql_core::parser::parser::ParserError;
+use gluesql_core::parser::tokenizer::TokenizerError;
+use gluesql_core::Error;
+
+use crate::web_storage::WebStorageError;
+
+#[derive(Clone, Copy)]
+pub struct Location {
+    pub line: u64,
+    pub column: u64,
+}
+
+/// Error rejected to JavaScript as an `Error` named `GlueError`, with `kind`, `statementIndex`
+/// and, for parse errors, `line` and `column` properties.
+/// Execution errors also carry the `payloads` of the statements which succeeded before.
+pub struct GlueError {
+    kind: &'static str,
+    message: String,
+    statement_index: Option<usize>,
+    location: Option<Location>,
+    payloads: Option<JsValue>,
+}
+
+impl GlueError {
+    pub fn at(self, statement_index: usize) -> Self {
+        Self {
+            statement_index: Some(statement_index),
+            ..self
+        }
+    }
+
+    pub fn located(self, location: Location) -> Self {
+        Self {
+            location: Some(location),
+            ..self
+        }
+    }
+
+    pub fn with_payloads(self, payloads: JsValue) -> Self {
+        Self {
+            payloads: Some(payloads),
+            ..self
+        }
+    }
+}
+
+impl From<ParserError> for GlueError {
+    fn from(error: ParserError) -> Self {
+        let message = match error {
+            ParserError::TokenizerError(message) | ParserError::ParserError(message) => message,
+        };
+
+        Self {
+            kind: "parse",
+            message,
+            statement_index: None,
+            location: None,
+            payloads: None,
+        }
+    }
+}
+
+impl From<TokenizerError> for GlueError {
+    fn from(error: TokenizerError) -> Self {
+        let TokenizerError { message, line, col } = error;
+
+        Self {
+            kind: "parse",
+            message,
+            statement_index: None,
+            location: Some(Location { line, column: col }),
+            payloads: None,
+        }
+    }
+}
+
+impl From<Error> for GlueError {
+    fn from(error: Error) -> Self {
+        let kind = match &error {
+            Error::AlterTable(_) => "alter-table",
+            Error::Storage(e) => match e.downcast_ref::<WebStorageError>() {
+                Some(WebStorageError::QuotaExceeded(_)) => "quota-exceeded",
+                _ => "storage",
+            },
+            _ => "execute",
+        };
+
+        Self {
+            kind,
+            message: error.to_string(),
+            statement_index: None,
+            location: None,
+            payloads: None,
+        }
+    }
+}
+
+impl From<GlueError> for JsValue {
+    fn from(error: GlueError) -> Self {
+        let GlueError {
+            kind,
+            message,
+            statement_index,
+            location,
+            payloads,
+        } = error;
+
+        let js_error = js_sys::Error::new(&message);
+        js_error.set_name("GlueError");
+
+        let set = |key: &str, value: JsValue| {
+            Reflect::set(&js_error, &JsValue::from_str(key), &value).unwrap();
+        };
+
+        set("kind", JsValue::from_str(kind));
+        set(
+            "statementIndex",
+            statement_index.map_or(JsValue::NULL, |i| JsValue::from_f64(i as f64)),
+        );
+
+        if let Some(Location { line, column }) = location {
+            set("line", JsValue::from_f64(line as f64));
+            set("column", JsValue::from_f64(column as f64));
+        }
+
+        if let Some(payloads) = payloads {
+            set("payloads", payloads);
+        }
+
+        js_error.into()
+    }
+}
@@ -1,19 +1,49 @@
+pub mod backup;
+pub mod catalog;
 mod convert;
+mod cursor;
+mod dump;
+mod error;
+mod live_query;
+mod lock;
 pub mod memory_storage;
+mod params;
+mod subscription;
+pub mod transaction;
+mod types;
 mod utils;
 pub mod web_storage;
 
-use js_sys::Promise;
-use std::cell::RefCell;
+use boolinator::Boolinator;
+use js_sys::{Array, Function, Object, Promise, Reflect, Uint8Array, JSON};
+use std::cell::{Cell, RefCell};
+use std::fmt::Debug;
+use std::future::Future;
 use std::rc::Rc;
-use wasm_bindgen_futures::future_to_promise;
+use wasm_bindgen_futures::{future_to_promise, JsFuture};
 
 use wasm_bindgen::prelude::*;
 
-use convert::convert;
+use gluesql_core::parser::ast::Statement;
+use gluesql_core::parser::parser::ParserError;
+use gluesql_core::{AlterTable, Error, MutResult, Payload, Query, Store, StoreMut};
 
+use backup::Backupable;
+use catalog::Catalog;
+use convert::{convert, convert_schemas, Output};
+use error::GlueError;
+use live_query::{changed_tables, Changes, LiveQueries};
+use params::{Params, Prepared};
+use subscription::Listeners;
+use transaction::Transaction;
+use web_storage::{js_error, WebStorageError};
+
+pub use cursor::Cursor;
 pub use memory_storage::MemoryStorage;
-pub use web_storage::{LocalStorage, SessionStorage};
+pub use subscription::Subscription;
+pub use web_storage::{
+    FsStorage, IndexedDbStorage, JsCallbackStorage, LocalStorage, OpfsStorage, SessionStorage,
+};
 
 // When the `wee_alloc` feature is enabled, use `wee_alloc` as the global
 // allocator.
@@ -32,11 +62,162 @@ enum Storage {
     Memory(MemoryStorage),
     Local(LocalStorage),
     Session(SessionStorage),
+    IndexedDb(IndexedDbStorage),
+    Opfs(OpfsStorage),
+    Fs(FsStorage),
+    Custom(JsCallbackStorage),
+}
+
+impl Storage {
+    fn in_transaction(&self) -> bool {
+        match self {
+            Storage::Local(storage) => storage.in_transaction(),
+            Storage::Session(storage) => storage.in_transaction(),
+            Storage::IndexedDb(storage) => storage.in_transaction(),
+            Storage::Opfs(storage) => storage.in_transaction(),
+            Storage::Fs(storage) => storage.in_transaction(),
+            Storage::Custom(storage) => storage.in_transaction(),
+            Storage::Memory(_) | Storage::Empty => false,
+        }
+    }
+
+    /// Waits for the writes of the last storage operation to be durable.
+    async fn complete(&self) -> Result<(), Error> {
+        match self {
+            Storage::Local(storage) => storage.complete().await,
+            Storage::Session(storage) => storage.complete().await,
+            Storage::IndexedDb(storage) => storage.complete().await,
+            Storage::Opfs(storage) => storage.complete().await,
+            Storage::Fs(storage) => storage.complete().await,
+            Storage::Custom(storage) => storage.complete().await,
+            Storage::Memory(_) | Storage::Empty => Ok(()),
+        }
+    }
+}
+
+/// Evaluates `$expr` with `$storage` bound to the storage taken out of `$cell`, which gets it
+/// back once the writes of `$expr` are durable.
+macro_rules! with_storage {
+    ($cell: expr, $storage: ident => $expr: expr) => {{
+        let storage = $cell.replace(Storage::Empty);
+
+        let result = match &storage {
+            Storage::Memory($storage) => $expr,
+            Storage::Local($storage) => $expr,
+            Storage::Session($storage) => $expr,
+            Storage::IndexedDb($storage) => $expr,
+            Storage::Opfs($storage) => $expr,
+            Storage::Fs($storage) => $expr,
+            Storage::Custom($storage) => $expr,
+            Storage::Empty => Err(Error::Storage("unreachable empty storage".into())),
+        };
+
+        let completed = storage.complete().await;
+        $cell.replace(storage);
+
+        result.and_then(|value| completed.map(|()| value))
+    }};
+}
+
+fn split<T, U>(result: MutResult<T, U>) -> (T, Result<U, Error>) {
+    match result {
+        Ok((storage, value)) => (storage, Ok(value)),
+        Err((storage, error)) => (storage, Err(error)),
+    }
+}
+
+/// Like `with_storage!`, but moves the storage into `$expr`, which returns a `MutResult`.
+macro_rules! with_storage_mut {
+    ($cell: expr, $storage: ident => $expr: expr) => {{
+        let (storage, result) = match $cell.replace(Storage::Empty) {
+            Storage::Memory($storage) => {
+                let (storage, result) = split($expr);
+
+                (Storage::Memory(storage), result)
+            }
+            Storage::Local($storage) => {
+                let (storage, result) = split($expr);
+
+                (Storage::Local(storage), result)
+            }
+            Storage::Session($storage) => {
+                let (storage, result) = split($expr);
+
+                (Storage::Session(storage), result)
+            }
+            Storage::IndexedDb($storage) => {
+                let (storage, result) = split($expr);
+
+                (Storage::IndexedDb(storage), result)
+            }
+            Storage::Opfs($storage) => {
+                let (storage, result) = split($expr);
+
+                (Storage::Opfs(storage), result)
+            }
+            Storage::Fs($storage) => {
+                let (storage, result) = split($expr);
+
+                (Storage::Fs(storage), result)
+            }
+            Storage::Custom($storage) => {
+                let (storage, result) = split($expr);
+
+                (Storage::Custom(storage), result)
+            }
+            Storage::Empty => (
+                Storage::Empty,
+                Err(Error::Storage("unreachable empty storage".into())),
+            ),
+        };
+
+        let completed = storage.complete().await;
+        $cell.replace(storage);
+
+        result.and_then(|value| completed.map(|()| value))
+    }};
+}
+
+/// Opens the `$namespace` of a storage type whose databases share a single key space, and
+/// evaluates `$expr` with it.
+macro_rules! with_namespace {
+    ($storage_type: expr, $namespace: expr, $storage: ident => $expr: expr) => {{
+        let namespace: String = $namespace;
+
+        macro_rules! open {
+            ($Storage: ident) => {
+                match $Storage::new(namespace) {
+                    Ok($storage) => $expr,
+                    Err(error) => Err(error),
+                }
+            };
+        }
+
+        match $storage_type {
+            "localstorage" => open!(LocalStorage),
+            "sessionstorage" => open!(SessionStorage),
+            "indexeddb" => open!(IndexedDbStorage),
+            "opfs" => open!(OpfsStorage),
+            _ => Err(Error::Storage(
+                "database options: localstorage | sessionstorage | indexeddb | opfs".into(),
+            )),
+        }
+    }};
 }
 
 #[wasm_bindgen]
+#[derive(Clone)]
 pub struct Glue {
     storage: Rc<RefCell<Storage>>,
+    atomic: Rc<Cell<bool>>,
+    object_rows: Rc<Cell<bool>>,
+    /// Settles after the last queued call, which the next call waits for.
+    queue: Rc<RefCell<Promise>>,
+    live_queries: Rc<RefCell<LiveQueries>>,
+    /// Listeners of `onChange`, which stay registered after their `Subscription` is freed.
+    listeners: Rc<RefCell<Listeners>>,
+    /// Web Lock taken by `BEGIN`, which is held until `COMMIT` or `ROLLBACK`.
+    transaction_lock: Rc<RefCell<Option<lock::Lock>>>,
 }
 
 #[wasm_bindgen]
@@ -62,81 +243,671 @@ impl Glue {
             "memory" => Storage::Memory(MemoryStorage::new().unwrap()),
             "localstorage" => Storage::Local(LocalStorage::new(get_namespace()?).unwrap()),
             "sessionstorage" => Storage::Session(SessionStorage::new(get_namespace()?).unwrap()),
+            "indexeddb" => Storage::IndexedDb(IndexedDbStorage::new(get_namespace()?).unwrap()),
+            "opfs" => Storage::Opfs(OpfsStorage::new(get_namespace()?).unwrap()),
+            "fs" => Storage::Fs(FsStorage::new(get_namespace()?).unwrap()),
             _ => {
                 let e = JsValue::from_str(
-                    "storage type options: memory | localstorage | sessionstorage",
+                    "storage type options: memory | localstorage | sessionstorage | indexeddb | opfs | fs",
                 );
                 return Err(e);
             }
         };
 
-        let storage = Rc::new(RefCell::new(storage));
-        log("[GlueSQL] ready to use :)");
+        Ok(Self::from_storage(storage))
+    }
+
+    /// Opens a storage backed by a JavaScript object with async `get(key)`, `set(key, value)`,
+    /// `remove(key)` and `keys(prefix)` methods, where keys and values are strings.
+    #[wasm_bindgen(js_name = withCustomStorage)]
+    pub fn with_custom_storage(
+        storage: JsValue,
+        namespace: Option<String>,
+    ) -> Result<Glue, JsValue> {
+        utils::set_panic_hook();
+
+        let namespace = namespace.unwrap_or_else(|| "custom".to_owned());
+        let storage = JsCallbackStorage::new(storage, namespace).map_err(GlueError::from)?;
+
+        Ok(Self::from_storage(Storage::Custom(storage)))
+    }
+
+    /// Opens a memory storage restored from bytes written by `snapshot`.
+    #[wasm_bindgen(js_name = fromSnapshot)]
+    pub fn from_snapshot(snapshot: &Uint8Array) -> Result<Glue, JsValue> {
+        utils::set_panic_hook();
+
+        let storage = MemoryStorage::from_bytes(&snapshot.to_vec()).map_err(GlueError::from)?;
 
-        Ok(Self { storage })
+        Ok(Self::from_storage(Storage::Memory(storage)))
+    }
+
+    /// Resolves to the namespaces which have data in the given storage type.
+    #[wasm_bindgen(js_name = listDatabases)]
+    pub fn list_databases(storage_type: String) -> Promise {
+        future_to_promise(async move {
+            with_namespace!(storage_type.as_str(), String::new(), storage => {
+                let namespaces = storage.list_namespaces().await;
+                let completed = storage.complete().await;
+
+                namespaces.and_then(|namespaces| completed.map(|()| namespaces))
+            })
+            .map(|namespaces| {
+                namespaces
+                    .iter()
+                    .map(JsValue::from)
+                    .collect::<Array>()
+                    .into()
+            })
+            .map_err(|error| GlueError::from(error).into())
+        })
+    }
+
+    /// Removes every table of the namespace.
+    /// `Glue` instances already open on it should not be used afterwards.
+    #[wasm_bindgen(js_name = dropDatabase)]
+    pub fn drop_database(storage_type: String, namespace: String) -> Promise {
+        future_to_promise(async move {
+            let _lock = lock::acquire_database(&storage_type, &namespace)
+                .await
+                .map_err(GlueError::from)?;
+
+            with_namespace!(storage_type.as_str(), namespace, storage => {
+                let dropped = storage.drop_namespace().await;
+                let completed = storage.complete().await;
+
+                dropped.and(completed)
+            })
+            .map(|()| JsValue::UNDEFINED)
+            .map_err(|error| GlueError::from(error).into())
+        })
+    }
+
+    /// Copies every table of the `source` namespace to `target`, which must not exist yet.
+    #[wasm_bindgen(js_name = copyDatabase)]
+    pub fn copy_database(storage_type: String, source: String, target: String) -> Promise {
+        future_to_promise(async move {
+            // Web Locks are not reentrant, the same lock requested twice never settles
+            if source == target {
+                let error = WebStorageError::NamespaceExists(target);
+
+                return Err(GlueError::from(Error::from(error)).into());
+            }
+
+            // both locks are taken in the same order by every tab
+            let mut names = [source.as_str(), target.as_str()];
+            names.sort_unstable();
+
+            let mut locks = vec![];
+            for namespace in names.iter() {
+                let lock = lock::acquire_database(&storage_type, namespace)
+                    .await
+                    .map_err(GlueError::from)?;
+
+                locks.push(lock);
+            }
+
+            with_namespace!(storage_type.as_str(), source, storage => {
+                let copied = storage.copy_namespace(&target).await;
+                let completed = storage.complete().await;
+
+                copied.and(completed)
+            })
+            .map(|()| JsValue::UNDEFINED)
+            .map_err(|error| GlueError::from(error).into())
+        })
+    }
+
+    /// When enabled, a failing statement rolls back every statement executed before it in the
+    /// same `execute` call.
+    #[wasm_bindgen(js_name = setAtomic)]
+    pub fn set_atomic(&mut self, atomic: bool) {
+        self.atomic.set(atomic);
+    }
+
+    /// When enabled, SELECT rows are objects keyed by column label instead of arrays.
+    #[wasm_bindgen(js_name = setObjectRows)]
+    pub fn set_object_rows(&mut self, object_rows: bool) {
+        self.object_rows.set(object_rows);
+    }
+
+    pub fn begin(&mut self) -> Promise {
+        self.execute("BEGIN;".to_owned())
+    }
+
+    pub fn commit(&mut self) -> Promise {
+        self.execute("COMMIT;".to_owned())
+    }
+
+    pub fn rollback(&mut self) -> Promise {
+        self.execute("ROLLBACK;".to_owned())
     }
 
     pub fn execute(&mut self, sql: String) -> Promise {
+        match params::parse(&sql) {
+            Ok(queries) => self.run(queries),
+            Err(error) => Promise::reject(&error.into()),
+        }
+    }
+
+    /// Binds `params` to `?` or `$1` placeholders in order.
+    #[wasm_bindgen(js_name = executeWithParams)]
+    pub fn execute_with_params(&mut self, sql: String, params: Array) -> Promise {
+        let queries = Params::from_array(&params)
+            .map_err(GlueError::from)
+            .and_then(|params| Prepared::new(&sql)?.bind(&params));
+
+        match queries {
+            Ok(queries) => self.run(queries),
+            Err(error) => Promise::reject(&error.into()),
+        }
+    }
+
+    /// Binds the properties of `params` to `$name` placeholders.
+    #[wasm_bindgen(js_name = executeWithNamedParams)]
+    pub fn execute_with_named_params(&mut self, sql: String, params: Object) -> Promise {
+        let queries = Params::from_object(&params)
+            .map_err(GlueError::from)
+            .and_then(|params| Prepared::new(&sql)?.bind(&params));
+
+        match queries {
+            Ok(queries) => self.run(queries),
+            Err(error) => Promise::reject(&error.into()),
+        }
+    }
+
+    /// Parses `sql` once, so it can be executed many times with different parameters.
+    pub fn prepare(&self, sql: String) -> Result<PreparedStatement, JsValue> {
+        let prepared = Prepared::new(&sql)?;
+
+        Ok(PreparedStatement {
+            glue: self.clone(),
+            prepared,
+        })
+    }
+
+    /// Resolves to the name and columns of every table, sorted by name.
+    pub fn tables(&self) -> Promise {
         let cell = Rc::clone(&self.storage);
+        let glue = self.clone();
 
-        future_to_promise(async move {
-            let queries = gluesql_core::parse(&sql).map_err(|error| {
-                let message = format!("{:?}", error);
+        self.enqueue(async move {
+            let _lock = glue.lock().await.map_err(GlueError::from)?;
+
+            with_storage!(cell, storage => storage.list_schemas().await)
+                .map(convert_schemas)
+                .map_err(|error| GlueError::from(error).into())
+        })
+    }
 
-                JsValue::from_serde(&message).unwrap()
-            })?;
+    /// Resolves to `[{ table, bytes }]`, an estimate of the bytes taken by the keys and values of
+    /// each table of a persistent storage.
+    #[wasm_bindgen(js_name = storageUsage)]
+    pub fn storage_usage(&self) -> Promise {
+        let cell = Rc::clone(&self.storage);
+        let glue = self.clone();
 
-            let mut payloads = vec![];
+        self.enqueue(async move {
+            let _lock = glue.lock().await.map_err(GlueError::from)?;
+            let storage = cell.replace(Storage::Empty);
+            let usage = match &storage {
+                Storage::Local(storage) => storage.storage_usage().await,
+                Storage::Session(storage) => storage.storage_usage().await,
+                Storage::IndexedDb(storage) => storage.storage_usage().await,
+                Storage::Opfs(storage) => storage.storage_usage().await,
+                Storage::Fs(storage) => storage.storage_usage().await,
+                Storage::Custom(storage) => storage.storage_usage().await,
+                Storage::Memory(_) | Storage::Empty => Err(Error::Storage(
+                    "storageUsage is only supported by persistent storages".into(),
+                )),
+            };
+            let completed = storage.complete().await;
+            cell.replace(storage);
 
-            macro_rules! execute {
-                ($storage: ident, $query: ident) => {
-                    match gluesql_core::execute($storage, $query).await {
-                        Ok((storage, payload)) => {
-                            payloads.push(payload);
+            let usage = usage
+                .and_then(|usage| completed.map(|()| usage))
+                .map_err(GlueError::from)?
+                .into_iter()
+                .map(|(table_name, bytes)| {
+                    let object = Object::new();
+                    let set = |key: &str, value: &JsValue| {
+                        Reflect::set(&object, &JsValue::from_str(key), value).unwrap();
+                    };
 
-                            (storage, Ok(()))
-                        }
-                        Err((storage, error)) => {
-                            (storage, Err(JsValue::from_serde(&error).unwrap()))
-                        }
-                    }
-                };
+                    set("table", &JsValue::from_str(&table_name));
+                    set("bytes", &JsValue::from_f64(bytes as f64));
+
+                    object
+                })
+                .collect::<Array>();
+
+            Ok(usage.into())
+        })
+    }
+
+    /// Resolves to a SQL script of `CREATE TABLE` and `INSERT` statements for every table.
+    pub fn dump(&self) -> Promise {
+        let cell = Rc::clone(&self.storage);
+        let glue = self.clone();
+
+        self.enqueue(async move {
+            let _lock = glue.lock().await.map_err(GlueError::from)?;
+
+            with_storage!(cell, storage => dump::dump(storage).await)
+                .map(|sql| JsValue::from_str(&sql))
+                .map_err(|error| GlueError::from(error).into())
+        })
+    }
+
+    /// Resolves to a `Uint8Array` with the tables of a memory storage, which `Glue.fromSnapshot`
+    /// restores.
+    pub fn snapshot(&self) -> Promise {
+        let cell = Rc::clone(&self.storage);
+
+        self.enqueue(async move {
+            match &*cell.borrow() {
+                Storage::Memory(storage) => storage
+                    .to_bytes()
+                    .map(|bytes| Uint8Array::from(bytes.as_slice()).into())
+                    .map_err(|error| GlueError::from(error).into()),
+                _ => Err(GlueError::from(Error::Storage(
+                    "snapshot is only supported by memory storage".into(),
+                ))
+                .into()),
             }
+        })
+    }
 
-            let mut storage: Storage = cell.replace(Storage::Empty);
+    /// Resolves to a versioned JSON document with the schema, rows and id counter of every
+    /// table.
+    #[wasm_bindgen(js_name = exportJson)]
+    pub fn export_json(&self) -> Promise {
+        let cell = Rc::clone(&self.storage);
+        let glue = self.clone();
 
-            for query in queries.iter() {
-                let result = match storage {
-                    Storage::Memory(s) => {
-                        let (s, result) = execute!(s, query);
+        self.enqueue(async move {
+            let _lock = glue.lock().await.map_err(GlueError::from)?;
+            let backup = with_storage!(cell, storage => storage.export_tables().await)
+                .and_then(backup::to_json)
+                .map_err(GlueError::from)?;
 
-                        storage = Storage::Memory(s);
-                        result
-                    }
-                    Storage::Local(s) => {
-                        let (s, result) = execute!(s, query);
+            JSON::parse(&backup).map_err(|error| GlueError::from(js_error(error)).into())
+        })
+    }
+
+    /// Imports a document written by `exportJson`, either as an object or a JSON string.
+    /// Tables of the same names are replaced, and nothing is imported if any table fails.
+    #[wasm_bindgen(js_name = importJson)]
+    pub fn import_json(&self, backup: JsValue) -> Promise {
+        let cell = Rc::clone(&self.storage);
+        let glue = self.clone();
+
+        self.enqueue(async move {
+            let backup = match backup.as_string() {
+                Some(backup) => backup,
+                None => JSON::stringify(&backup)
+                    .map_err(|error| GlueError::from(js_error(error)))?
+                    .into(),
+            };
+            let tables = backup::from_json(&backup).map_err(GlueError::from)?;
+            let _lock = glue.lock().await.map_err(GlueError::from)?;
 
-                        storage = Storage::Local(s);
-                        result
+            with_storage_mut!(cell, storage => storage.import_tables(tables).await)
+                .map_err(GlueError::from)?;
+            glue.refresh(&Changes::All);
+
+            Ok(JsValue::UNDEFINED)
+        })
+    }
+
+    /// Resolves to a `Subscription` which calls `callback` with `{ table, kind }` whenever another
+    /// tab changes a table of this `localstorage` database.
+    /// `kind` is one of `create`, `alter`, `drop`, `insert`, `update` and `delete`.
+    #[wasm_bindgen(js_name = onChange)]
+    pub fn on_change(&self, callback: Function) -> Promise {
+        let cell = Rc::clone(&self.storage);
+        let listeners = Rc::clone(&self.listeners);
+
+        self.enqueue(async move {
+            let namespace = match &*cell.borrow() {
+                Storage::Local(storage) => storage.namespace().to_owned(),
+                _ => {
+                    let error = Error::Storage("onChange is only supported by localstorage".into());
+
+                    return Err(GlueError::from(error).into());
+                }
+            };
+
+            subscription::listen_storage_events(listeners, namespace, callback).map(JsValue::from)
+        })
+    }
+
+    /// Resolves to a `Subscription` which calls `callback` with the result of a single SELECT,
+    /// first right away and then whenever this `Glue` changes a table the SELECT reads.
+    /// If a later run fails, `callback` receives the error as its second argument instead.
+    pub fn subscribe(&self, sql: String, callback: Function) -> Promise {
+        let glue = self.clone();
+        let object_rows = self.object_rows.get();
+
+        self.enqueue(async move {
+            let mut queries = parse_select(&sql, "subscribe")?;
+            let (outputs, result) = glue.run_queries(&queries).await;
+            result?;
+
+            let payload = Array::from(&convert(outputs, object_rows)).get(0);
+            callback.call1(&JsValue::NULL, &payload)?;
+
+            let Query(statement) = queries.remove(0);
+            let id = glue.live_queries.borrow_mut().add(statement, callback);
+            let live_queries = Rc::clone(&glue.live_queries);
+            let subscription = Subscription::new(move || live_queries.borrow_mut().remove(id));
+
+            Ok(subscription.into())
+        })
+    }
+
+    /// Resolves to a `Cursor` over the rows of a single SELECT statement, which yields them in
+    /// batches of `batch_size` rows, 100 by default.
+    #[wasm_bindgen(js_name = queryCursor)]
+    pub fn query_cursor(&self, sql: String, batch_size: Option<usize>) -> Promise {
+        let glue = self.clone();
+        let batch_size = batch_size.unwrap_or(100);
+        let object_rows = self.object_rows.get();
+
+        self.enqueue(async move {
+            let queries = parse_select(&sql, "queryCursor")?;
+            let (mut outputs, result) = glue.run_queries(&queries).await;
+            result?;
+
+            match outputs.pop() {
+                Some(Output::Select {
+                    labels,
+                    types,
+                    rows,
+                }) => Ok(Cursor::new(labels, types, rows, batch_size, object_rows).into()),
+                _ => Err(
+                    GlueError::from(Error::Storage("unreachable non select output".into())).into(),
+                ),
+            }
+        })
+    }
+}
+
+impl Glue {
+    fn from_storage(storage: Storage) -> Self {
+        log("[GlueSQL] ready to use :)");
+
+        Self {
+            storage: Rc::new(RefCell::new(storage)),
+            atomic: Rc::new(Cell::new(false)),
+            object_rows: Rc::new(Cell::new(false)),
+            queue: Rc::new(RefCell::new(Promise::resolve(&JsValue::UNDEFINED))),
+            live_queries: Rc::new(RefCell::new(LiveQueries::default())),
+            listeners: Rc::new(RefCell::new(Listeners::default())),
+            transaction_lock: Rc::new(RefCell::new(None)),
+        }
+    }
+
+    /// Runs `future` once every call queued before it has settled, so that overlapping calls
+    /// never find the storage taken by another one.
+    fn enqueue<F>(&self, future: F) -> Promise
+    where
+        F: Future<Output = Result<JsValue, JsValue>> + 'static,
+    {
+        let previous = self.queue.borrow().clone();
+        let promise = future_to_promise(async move {
+            // the previous call reports its own error
+            let _ = JsFuture::from(previous).await;
+
+            future.await
+        });
+
+        *self.queue.borrow_mut() = promise.clone();
+
+        promise
+    }
+
+    fn run(&self, queries: Vec<Query>) -> Promise {
+        let glue = self.clone();
+        let object_rows = self.object_rows.get();
+
+        self.enqueue(async move {
+            let (outputs, result) = glue.run_queries(&queries).await;
+            glue.refresh(&changed_tables(&queries, &outputs));
+
+            let payloads = convert(outputs, object_rows);
+
+            match result {
+                Ok(()) => Ok(payloads),
+                Err(error) => Err(error.with_payloads(payloads).into()),
+            }
+        })
+    }
+
+    /// Takes the Web Lock of a database shared by every tab, so that writes of other tabs never
+    /// interleave with the ones of this call.
+    /// Resolves to `None` inside a transaction, whose lock is already held.
+    async fn lock(&self) -> Result<Option<lock::Lock>, Error> {
+        if self.transaction_lock.borrow().is_some() {
+            return Ok(None);
+        }
+
+        let (storage_type, namespace) = match &*self.storage.borrow() {
+            Storage::Local(storage) => ("localstorage", storage.namespace().to_owned()),
+            Storage::IndexedDb(storage) => ("indexeddb", storage.namespace().to_owned()),
+            Storage::Opfs(storage) => ("opfs", storage.namespace().to_owned()),
+            _ => return Ok(None),
+        };
+
+        lock::acquire_database(storage_type, &namespace).await
+    }
+
+    /// Queues a run of every live query which reads a changed table.
+    fn refresh(&self, changes: &Changes) {
+        let object_rows = self.object_rows.get();
+
+        for (query, callback) in self.live_queries.borrow().affected(changes) {
+            let glue = self.clone();
+
+            // the callback rejects the queued promise if it throws, nobody waits for it
+            let _ = self.enqueue(async move {
+                let queries = [query];
+                let (outputs, result) = glue.run_queries(&queries).await;
+
+                match result {
+                    Ok(()) => {
+                        let payload = Array::from(&convert(outputs, object_rows)).get(0);
+
+                        callback.call1(&JsValue::NULL, &payload)
                     }
-                    Storage::Session(s) => {
-                        let (s, result) = execute!(s, query);
+                    Err(error) => {
+                        callback.call2(&JsValue::NULL, &JsValue::UNDEFINED, &error.into())
+                    }
+                }
+            });
+        }
+    }
+
+    async fn run_queries(&self, queries: &[Query]) -> (Vec<Output>, Result<(), GlueError>) {
+        let cell = &self.storage;
+        let atomic = self.atomic.get();
+
+        macro_rules! execute_journaled {
+            ($Variant: ident, $storage: ident) => {{
+                let begin = if atomic {
+                    $storage.begin_journal().await
+                } else {
+                    Ok(())
+                };
+
+                match begin {
+                    Ok(()) => {
+                        let (storage, outputs, result) = execute($storage, queries).await;
+                        let result = match (atomic, result) {
+                            (false, result) => result,
+                            (true, Ok(())) => {
+                                storage.commit_journal().await.map_err(GlueError::from)
+                            }
+                            (true, Err(error)) => storage
+                                .rollback_journal()
+                                .await
+                                .map_err(GlueError::from)
+                                .and(Err(error)),
+                        };
 
-                        storage = Storage::Session(s);
-                        result
+                        (Storage::$Variant(storage), outputs, result)
                     }
-                    Storage::Empty => Err(JsValue::from_str("unreachable empty storage")),
+                    Err(error) => (Storage::$Variant($storage), vec![], Err(error.into())),
+                }
+            }};
+        }
+
+        let lock = match self.lock().await {
+            Ok(lock) => lock,
+            Err(error) => return (vec![], Err(error.into())),
+        };
+
+        let storage: Storage = cell.replace(Storage::Empty);
+
+        let (storage, outputs, result) = match storage {
+            Storage::Memory(storage) => {
+                let snapshot = atomic.as_some(storage.clone());
+                let (storage, outputs, result) = execute(storage, queries).await;
+                let storage = match (result.is_err(), snapshot) {
+                    (true, Some(snapshot)) => snapshot,
+                    _ => storage,
                 };
 
-                if let Err(e) = result {
-                    cell.replace(storage);
+                (Storage::Memory(storage), outputs, result)
+            }
+            Storage::Local(storage) => execute_journaled!(Local, storage),
+            Storage::Session(storage) => execute_journaled!(Session, storage),
+            Storage::IndexedDb(storage) => execute_journaled!(IndexedDb, storage),
+            Storage::Opfs(storage) => execute_journaled!(Opfs, storage),
+            Storage::Fs(storage) => execute_journaled!(Fs, storage),
+            Storage::Custom(storage) => execute_journaled!(Custom, storage),
+            Storage::Empty => (
+                Storage::Empty,
+                vec![],
+                Err(Error::Storage("unreachable empty storage".into()).into()),
+            ),
+        };
+
+        // an IndexedDB transaction which aborts while committing undoes the writes of the outputs
+        let (outputs, result) = match storage.complete().await {
+            Ok(()) => (outputs, result),
+            Err(error) => (vec![], Err(error.into())),
+        };
 
-                    return Err(e);
+        // the lock of a transaction is held from BEGIN until COMMIT or ROLLBACK
+        match storage.in_transaction() {
+            true => {
+                if let Some(lock) = lock {
+                    self.transaction_lock.replace(Some(lock));
                 }
             }
+            false => {
+                self.transaction_lock.replace(None);
+            }
+        }
 
-            cell.replace(storage);
-            Ok(convert(payloads))
-        })
+        cell.replace(storage);
+
+        (outputs, result)
+    }
+}
+
+#[wasm_bindgen]
+pub struct PreparedStatement {
+    glue: Glue,
+    prepared: Prepared,
+}
+
+#[wasm_bindgen]
+impl PreparedStatement {
+    /// Binds an array of positional parameters or an object of named parameters, or nothing
+    /// when `params` is omitted.
+    pub fn execute(&self, params: JsValue) -> Promise {
+        let queries = Params::from_js(&params)
+            .map_err(GlueError::from)
+            .and_then(|params| self.prepared.bind(&params));
+
+        match queries {
+            Ok(queries) => self.glue.run(queries),
+            Err(error) => Promise::reject(&error.into()),
+        }
+    }
+}
+
+fn parse_select(sql: &str, method: &str) -> Result<Vec<Query>, GlueError> {
+    let queries = params::parse(sql)?;
+
+    match queries.as_slice() {
+        [Query(Statement::Query(_))] => Ok(queries),
+        _ => {
+            let message = format!("{} only accepts a single SELECT statement", method);
+
+            Err(ParserError::ParserError(message).into())
+        }
+    }
+}
+
+/// Runs `queries` until one fails, returning the outputs of the ones which succeeded.
+async fn execute<T, U>(storage: U, queries: &[Query]) -> (U, Vec<Output>, Result<(), GlueError>)
+where
+    T: 'static + Debug,
+    U: Store<T> + StoreMut<T> + AlterTable + Transaction + Catalog,
+{
+    let mut storage = storage;
+    let mut outputs = vec![];
+
+    for (i, query) in queries.iter().enumerate() {
+        let Query(statement) = query;
+
+        let result = match statement {
+            Statement::StartTransaction { .. } => {
+                storage.begin().await.map(|(s, _)| (s, Output::Begin))
+            }
+            Statement::Commit { .. } => storage.commit().await.map(|(s, _)| (s, Output::Commit)),
+            Statement::Rollback { .. } => {
+                storage.rollback().await.map(|(s, _)| (s, Output::Rollback))
+            }
+            Statement::ShowVariable { variable }
+                if variable.value.eq_ignore_ascii_case("TABLES") =>
+            {
+                match storage.list_schemas().await {
+                    Ok(schemas) => Ok((storage, Output::ShowTables(schemas))),
+                    Err(error) => Err((storage, error)),
+                }
+            }
+            _ => match gluesql_core::execute(storage, query).await {
+                Ok((s, Payload::Select { labels, rows })) => {
+                    match types::fetch_types(&s, statement).await {
+                        Ok(types) => Ok((
+                            s,
+                            Output::Select {
+                                labels,
+                                types,
+                                rows,
+                            },
+                        )),
+                        Err(error) => Err((s, error)),
+                    }
+                }
+                result => result.map(|(s, payload)| (s, Output::Payload(payload))),
+            },
+        };
+
+        match result {
+            Ok((s, output)) => {
+                storage = s;
+                outputs.push(output);
+            }
+            Err((s, error)) => {
+                return (s, outputs, Err(GlueError::from(error).at(i)));
+            }
+        }
     }
+
+    (storage, outputs, Ok(()))
 }
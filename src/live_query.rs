@@ -0,0 +1,198 @@
+use js_sys::Function;
+use std::collections::HashSet;
+
+use gluesql_core::parser::ast::{
+    Expr, JoinConstraint, JoinOperator, ObjectName, Query as AstQuery, SelectItem, SetExpr,
+    Statement, TableFactor, TableWithJoins,
+};
+use gluesql_core::Query;
+
+use crate::convert::Output;
+
+/// SELECT registered by `Glue::subscribe`, which is run again whenever a table it reads changes.
+pub struct LiveQuery {
+    id: u32,
+    statement: Statement,
+    callback: Function,
+    tables: HashSet<String>,
+}
+
+/// Tables changed by the statements of an `execute` call.
+pub enum Changes {
+    Tables(HashSet<String>),
+    /// A rollback, or an import, may restore any table.
+    All,
+}
+
+#[derive(Default)]
+pub struct LiveQueries {
+    next_id: u32,
+    queries: Vec<LiveQuery>,
+}
+
+impl LiveQueries {
+    pub fn add(&mut self, statement: Statement, callback: Function) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        let tables = read_tables(&statement);
+        self.queries.push(LiveQuery {
+            id,
+            statement,
+            callback,
+            tables,
+        });
+
+        id
+    }
+
+    pub fn remove(&mut self, id: u32) {
+        self.queries.retain(|live_query| live_query.id != id);
+    }
+
+    /// Live queries which read any of the changed tables.
+    pub fn affected(&self, changes: &Changes) -> Vec<(Query, Function)> {
+        self.queries
+            .iter()
+            .filter(|live_query| match changes {
+                Changes::All => true,
+                Changes::Tables(tables) => !live_query.tables.is_disjoint(tables),
+            })
+            .map(|live_query| {
+                let query = Query(live_query.statement.clone());
+
+                (query, live_query.callback.clone())
+            })
+            .collect()
+    }
+}
+
+fn table_name(name: &ObjectName) -> Option<String> {
+    name.0.last().map(|ident| ident.value.to_owned())
+}
+
+/// Tables changed by the statements which produced `outputs`, the ones which succeeded.
+pub fn changed_tables(queries: &[Query], outputs: &[Output]) -> Changes {
+    if outputs
+        .iter()
+        .any(|output| matches!(output, Output::Rollback))
+    {
+        return Changes::All;
+    }
+
+    let tables = queries
+        .iter()
+        .take(outputs.len())
+        .flat_map(|Query(statement)| match statement {
+            Statement::Insert { table_name, .. }
+            | Statement::Update { table_name, .. }
+            | Statement::Delete { table_name, .. }
+            | Statement::CreateTable {
+                name: table_name, ..
+            }
+            | Statement::AlterTable {
+                name: table_name, ..
+            } => vec![table_name],
+            Statement::Drop { names, .. } => names.iter().collect(),
+            _ => vec![],
+        })
+        .filter_map(table_name)
+        .collect();
+
+    Changes::Tables(tables)
+}
+
+/// Tables read by a SELECT, including the ones of its subqueries.
+fn read_tables(statement: &Statement) -> HashSet<String> {
+    let mut tables = HashSet::new();
+
+    if let Statement::Query(query) = statement {
+        TableCollector(&mut tables).query(query);
+    }
+
+    tables
+}
+
+struct TableCollector<'a>(&'a mut HashSet<String>);
+
+impl<'a> TableCollector<'a> {
+    fn query(&mut self, query: &AstQuery) {
+        for cte in query.ctes.iter() {
+            self.query(&cte.query);
+        }
+
+        self.set_expr(&query.body);
+    }
+
+    fn set_expr(&mut self, set_expr: &SetExpr) {
+        match set_expr {
+            SetExpr::Select(select) => {
+                for item in select.projection.iter() {
+                    match item {
+                        SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                            self.expr(expr)
+                        }
+                        SelectItem::QualifiedWildcard(_) | SelectItem::Wildcard => {}
+                    }
+                }
+
+                for table in select.from.iter() {
+                    self.table_with_joins(table);
+                }
+
+                for expr in select.selection.iter().chain(select.having.iter()) {
+                    self.expr(expr);
+                }
+            }
+            SetExpr::Query(query) => self.query(query),
+            SetExpr::SetOperation { left, right, .. } => {
+                self.set_expr(left);
+                self.set_expr(right);
+            }
+            SetExpr::Values(_) => {}
+        }
+    }
+
+    fn table_with_joins(&mut self, table: &TableWithJoins) {
+        self.table_factor(&table.relation);
+
+        for join in table.joins.iter() {
+            self.table_factor(&join.relation);
+
+            match &join.join_operator {
+                JoinOperator::Inner(JoinConstraint::On(expr))
+                | JoinOperator::LeftOuter(JoinConstraint::On(expr))
+                | JoinOperator::RightOuter(JoinConstraint::On(expr))
+                | JoinOperator::FullOuter(JoinConstraint::On(expr)) => self.expr(expr),
+                _ => {}
+            }
+        }
+    }
+
+    fn table_factor(&mut self, table_factor: &TableFactor) {
+        match table_factor {
+            TableFactor::Table { name, .. } => self.0.extend(table_name(name)),
+            TableFactor::Derived { subquery, .. } => self.query(subquery),
+            TableFactor::NestedJoin(table) => self.table_with_joins(table),
+        }
+    }
+
+    fn expr(&mut self, expr: &Expr) {
+        match expr {
+            Expr::IsNull(expr)
+            | Expr::IsNotNull(expr)
+            | Expr::UnaryOp { expr, .. }
+            | Expr::Nested(expr) => self.expr(expr),
+            Expr::InSubquery { expr, subquery, .. } => {
+                self.expr(expr);
+                self.query(subquery);
+            }
+            Expr::BinaryOp { left, right, .. } => {
+                self.expr(left);
+                self.expr(right);
+            }
+            Expr::Exists(query) | Expr::Subquery(query) => self.query(query),
+            _ => {}
+        }
+    }
+}
@@ -0,0 +1,76 @@
+use js_sys::{Array, Function, Promise, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use gluesql_core::Result;
+
+use crate::web_storage::js_error;
+
+/// Web Lock shared by every tab of the origin, which is released when dropped.
+pub struct Lock {
+    release: Function,
+}
+
+impl Drop for Lock {
+    fn drop(&mut self) {
+        let _ = self.release.call0(&JsValue::NULL);
+    }
+}
+
+/// Waits until no other tab holds the lock `name`, and holds it until the returned `Lock` is
+/// dropped.
+/// Resolves to `None` where `navigator.locks` is not available, as in Node.js.
+pub async fn acquire(name: &str) -> Result<Option<Lock>> {
+    let locks = Reflect::get(&js_sys::global(), &JsValue::from_str("navigator"))
+        .and_then(|navigator| match navigator.is_undefined() {
+            true => Ok(JsValue::UNDEFINED),
+            false => Reflect::get(&navigator, &JsValue::from_str("locks")),
+        })
+        .map_err(js_error)?;
+
+    if locks.is_undefined() {
+        return Ok(None);
+    }
+
+    // the lock is held until `held` settles, which happens once `release` is called
+    let mut release = None;
+    let held = Promise::new(&mut |resolve, _| release = Some(resolve));
+    let release = release.unwrap();
+
+    let mut granted = None;
+    let acquired = Promise::new(&mut |resolve, _| granted = Some(resolve));
+    let granted = granted.unwrap();
+
+    let callback = Closure::once_into_js(move || {
+        let _ = granted.call0(&JsValue::NULL);
+
+        held
+    });
+
+    let request: Function = Reflect::get(&locks, &JsValue::from_str("request"))
+        .map_err(js_error)?
+        .unchecked_into();
+    let requested: Promise = request
+        .call2(&locks, &JsValue::from_str(name), &callback)
+        .map_err(js_error)?
+        .unchecked_into();
+
+    // `requested` settles only once the lock is released, or rejects if the request fails
+    JsFuture::from(Promise::race(&Array::of2(&acquired, &requested)))
+        .await
+        .map_err(js_error)?;
+
+    Ok(Some(Lock { release }))
+}
+
+/// Takes the lock of the database `namespace` for the storage types shared by every tab of the
+/// origin, which are `localstorage`, `indexeddb` and `opfs`.
+pub async fn acquire_database(storage_type: &str, namespace: &str) -> Result<Option<Lock>> {
+    match storage_type {
+        "localstorage" | "indexeddb" | "opfs" => {
+            acquire(&format!("{}/{}", storage_type, namespace)).await
+        }
+        _ => Ok(None),
+    }
+}
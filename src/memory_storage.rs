@@ -1,16 +1,59 @@
 use async_trait::async_trait;
 use boolinator::Boolinator;
 use im::{vector, HashMap, Vector};
+use serde::{Deserialize, Serialize};
+use std::fmt;
 
 use gluesql_core::parser::ast::{ColumnDef, ColumnOption, ColumnOptionDef, Value as AstValue};
 use gluesql_core::{
-    AlterTable, AlterTableError, MutResult, Result, Row, RowIter, Schema, Store, StoreMut, Value,
+    AlterTable, AlterTableError, Error, MutResult, Result, Row, RowIter, Schema, Store, StoreMut,
+    Value,
 };
 
+use crate::backup::{Backupable, TableBackup};
+use crate::catalog::Catalog;
+use crate::transaction::{Transaction, TransactionError};
+
+#[derive(Clone)]
 pub struct MemoryStorage {
     schema_map: HashMap<String, Schema>,
     data_map: HashMap<String, Vector<(u64, Row)>>,
     id: u64,
+    snapshot: Option<Box<MemoryStorage>>,
+}
+
+/// Version of the encoding written by `MemoryStorage::to_bytes`.
+pub const SNAPSHOT_VERSION: u32 = 1;
+
+#[derive(Debug)]
+pub enum SnapshotError {
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for SnapshotError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SnapshotError::UnsupportedVersion(v) => {
+                write!(f, "unsupported snapshot version: {}", v)
+            }
+        }
+    }
+}
+
+impl std::error::Error for SnapshotError {}
+
+impl From<SnapshotError> for Error {
+    fn from(e: SnapshotError) -> Self {
+        Error::Storage(Box::new(e))
+    }
+}
+
+#[derive(Serialize, Deserialize)]
+struct Snapshot {
+    version: u32,
+    schema_map: HashMap<String, Schema>,
+    data_map: HashMap<String, Vector<(u64, Row)>>,
+    id: u64,
 }
 
 #[derive(Clone, Debug)]
@@ -28,6 +71,44 @@ impl MemoryStorage {
             schema_map,
             data_map,
             id: 0,
+            snapshot: None,
+        })
+    }
+
+    /// Encodes the tables and the id counter as bytes, which `from_bytes` restores.
+    /// Changes of a transaction in progress are left out.
+    pub fn to_bytes(&self) -> Result<Vec<u8>> {
+        let committed = self.snapshot.as_deref().unwrap_or(self);
+        let snapshot = Snapshot {
+            version: SNAPSHOT_VERSION,
+            schema_map: committed.schema_map.clone(),
+            data_map: committed.data_map.clone(),
+            id: committed.id,
+        };
+
+        bincode::serialize(&snapshot).map_err(|e| Error::Storage(e))
+    }
+
+    pub fn from_bytes(bytes: &[u8]) -> Result<Self> {
+        // the version leads the encoding, so it is checked before the rest is decoded
+        let version: u32 = bincode::deserialize(bytes).map_err(|e| Error::Storage(e))?;
+
+        if version != SNAPSHOT_VERSION {
+            return Err(SnapshotError::UnsupportedVersion(version).into());
+        }
+
+        let Snapshot {
+            schema_map,
+            data_map,
+            id,
+            ..
+        } = bincode::deserialize(bytes).map_err(|e| Error::Storage(e))?;
+
+        Ok(Self {
+            schema_map,
+            data_map,
+            id,
+            snapshot: None,
         })
     }
 }
@@ -40,6 +121,7 @@ impl StoreMut<DataKey> for MemoryStorage {
             schema_map: self.schema_map,
             data_map: self.data_map,
             id,
+            snapshot: self.snapshot,
         };
 
         let key = DataKey {
@@ -57,6 +139,7 @@ impl StoreMut<DataKey> for MemoryStorage {
             schema_map,
             data_map: self.data_map,
             id: self.id,
+            snapshot: self.snapshot,
         };
 
         Ok((storage, ()))
@@ -67,6 +150,7 @@ impl StoreMut<DataKey> for MemoryStorage {
             mut schema_map,
             mut data_map,
             id,
+            snapshot,
         } = self;
 
         data_map.remove(table_name);
@@ -75,6 +159,7 @@ impl StoreMut<DataKey> for MemoryStorage {
             schema_map,
             data_map,
             id,
+            snapshot,
         };
 
         Ok((storage, ()))
@@ -88,6 +173,7 @@ impl StoreMut<DataKey> for MemoryStorage {
             schema_map,
             data_map,
             id: self_id,
+            snapshot,
         } = self;
 
         let (mut items, data_map) = match data_map.extract(&table_name) {
@@ -109,6 +195,7 @@ impl StoreMut<DataKey> for MemoryStorage {
             schema_map,
             data_map,
             id: self_id,
+            snapshot,
         };
 
         Ok((storage, ()))
@@ -121,6 +208,7 @@ impl StoreMut<DataKey> for MemoryStorage {
             schema_map,
             data_map,
             id: self_id,
+            snapshot,
         } = self;
 
         let (mut items, data_map) = match data_map.extract(&table_name) {
@@ -137,6 +225,7 @@ impl StoreMut<DataKey> for MemoryStorage {
             schema_map,
             data_map,
             id: self_id,
+            snapshot,
         };
 
         Ok((storage, ()))
@@ -173,6 +262,72 @@ impl Store<DataKey> for MemoryStorage {
     }
 }
 
+#[async_trait(?Send)]
+impl Catalog for MemoryStorage {
+    async fn list_schemas(&self) -> Result<Vec<Schema>> {
+        let mut schemas = self.schema_map.values().cloned().collect::<Vec<_>>();
+        schemas.sort_by(|a, b| a.table_name.cmp(&b.table_name));
+
+        Ok(schemas)
+    }
+}
+
+#[async_trait(?Send)]
+impl Backupable for MemoryStorage {
+    async fn export_tables(&self) -> Result<Vec<TableBackup>> {
+        let tables = self
+            .list_schemas()
+            .await?
+            .into_iter()
+            .map(|schema| {
+                let rows = match self.data_map.get(&schema.table_name) {
+                    Some(items) => items.iter().cloned().collect(),
+                    None => vec![],
+                };
+
+                TableBackup {
+                    schema,
+                    id: self.id,
+                    rows,
+                }
+            })
+            .collect();
+
+        Ok(tables)
+    }
+
+    async fn import_tables(self, tables: Vec<TableBackup>) -> MutResult<Self, ()> {
+        let Self {
+            mut schema_map,
+            mut data_map,
+            mut id,
+            snapshot,
+        } = self;
+
+        for TableBackup {
+            schema,
+            id: table_id,
+            rows,
+        } in tables
+        {
+            let table_name = schema.table_name.to_string();
+
+            data_map.insert(table_name.clone(), rows.into_iter().collect());
+            schema_map.insert(table_name, schema);
+            id = id.max(table_id);
+        }
+
+        let storage = Self {
+            schema_map,
+            data_map,
+            id,
+            snapshot,
+        };
+
+        Ok((storage, ()))
+    }
+}
+
 macro_rules! try_into {
     ($self: expr, $expr: expr) => {
         match $expr {
@@ -184,6 +339,7 @@ macro_rules! try_into {
     };
 }
 
+#[allow(clippy::map_clone, clippy::needless_borrow)]
 #[async_trait(?Send)]
 impl AlterTable for MemoryStorage {
     async fn rename_schema(self, table_name: &str, new_table_name: &str) -> MutResult<Self, ()> {
@@ -201,6 +357,7 @@ impl AlterTable for MemoryStorage {
             schema_map,
             data_map,
             id,
+            snapshot,
         } = self;
 
         let mut schema_map = schema_map.update(new_table_name.to_string(), schema);
@@ -217,6 +374,7 @@ impl AlterTable for MemoryStorage {
             schema_map,
             data_map,
             id,
+            snapshot,
         };
 
         Ok((storage, ()))
@@ -249,6 +407,7 @@ impl AlterTable for MemoryStorage {
             schema_map,
             data_map,
             id,
+            snapshot,
         } = self;
 
         let schema_map = schema_map.update(table_name.to_string(), schema);
@@ -257,6 +416,7 @@ impl AlterTable for MemoryStorage {
             schema_map,
             data_map,
             id,
+            snapshot,
         };
 
         Ok((storage, ()))
@@ -288,6 +448,7 @@ impl AlterTable for MemoryStorage {
             schema_map,
             data_map,
             id,
+            snapshot,
         } = self;
 
         schema.column_defs.push(column_def.clone());
@@ -312,6 +473,7 @@ impl AlterTable for MemoryStorage {
             schema_map,
             data_map,
             id,
+            snapshot,
         };
 
         let value = match (default, nullable) {
@@ -332,6 +494,7 @@ impl AlterTable for MemoryStorage {
             schema_map,
             data_map,
             id,
+            snapshot,
         } = storage;
 
         let (items, data_map) = match data_map.extract(table_name) {
@@ -355,6 +518,7 @@ impl AlterTable for MemoryStorage {
             schema_map,
             data_map,
             id,
+            snapshot,
         };
 
         Ok((storage, ()))
@@ -395,6 +559,7 @@ impl AlterTable for MemoryStorage {
             schema_map,
             data_map,
             id,
+            snapshot,
         } = self;
 
         let (items, data_map) = match data_map.extract(table_name) {
@@ -430,8 +595,43 @@ impl AlterTable for MemoryStorage {
             schema_map,
             data_map,
             id,
+            snapshot,
         };
 
         Ok((storage, ()))
     }
 }
+
+#[async_trait(?Send)]
+impl Transaction for MemoryStorage {
+    async fn begin(self) -> MutResult<Self, ()> {
+        if self.snapshot.is_some() {
+            return Err((self, TransactionError::AlreadyStarted.into()));
+        }
+
+        let snapshot = Some(Box::new(self.clone()));
+        let storage = Self { snapshot, ..self };
+
+        Ok((storage, ()))
+    }
+
+    async fn commit(self) -> MutResult<Self, ()> {
+        if self.snapshot.is_none() {
+            return Err((self, TransactionError::NotStarted.into()));
+        }
+
+        let storage = Self {
+            snapshot: None,
+            ..self
+        };
+
+        Ok((storage, ()))
+    }
+
+    async fn rollback(self) -> MutResult<Self, ()> {
+        match self.snapshot {
+            Some(snapshot) => Ok((*snapshot, ())),
+            None => Err((self, TransactionError::NotStarted.into())),
+        }
+    }
+}
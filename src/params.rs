@@ -0,0 +1,539 @@
+use js_sys::{Array, Object};
+use std::collections::HashMap;
+use std::iter::Peekable;
+use wasm_bindgen::prelude::*;
+
+use gluesql_core::parser::ast::{
+    Expr, Ident, Join, JoinConstraint, JoinOperator, Query as AstQuery, Select, SelectItem,
+    SetExpr, Statement, TableFactor, TableWithJoins, Value as AstValue,
+};
+use gluesql_core::parser::dialect::GenericDialect;
+use gluesql_core::parser::parser::{Parser, ParserError};
+use gluesql_core::parser::tokenizer::{Token, Tokenizer, Whitespace};
+use gluesql_core::{Query, Value};
+
+use crate::error::{GlueError, Location};
+
+// placeholders are parsed as identifiers with this quote style and replaced while binding
+const MARKER: char = '$';
+
+/// Values bound to `?` and `$1` (positional) or `$name` (named) placeholders.
+pub enum Params {
+    Positional(Vec<Value>),
+    Named(HashMap<String, Value>),
+}
+
+impl Params {
+    pub fn from_array(params: &Array) -> Result<Self, ParserError> {
+        params
+            .iter()
+            .map(|param| convert_param(&param))
+            .collect::<Result<_, _>>()
+            .map(Params::Positional)
+    }
+
+    pub fn from_object(params: &Object) -> Result<Self, ParserError> {
+        Object::entries(params)
+            .iter()
+            .map(|entry| {
+                let entry = Array::from(&entry);
+                let name = entry.get(0).as_string().unwrap_or_default();
+
+                convert_param(&entry.get(1)).map(|value| (name, value))
+            })
+            .collect::<Result<_, _>>()
+            .map(Params::Named)
+    }
+
+    pub fn from_js(params: &JsValue) -> Result<Self, ParserError> {
+        if params.is_null() || params.is_undefined() {
+            Ok(Params::Positional(vec![]))
+        } else if Array::is_array(params) {
+            Params::from_array(params.unchecked_ref())
+        } else if params.is_object() {
+            Params::from_object(params.unchecked_ref())
+        } else {
+            Err(ParserError::ParserError(
+                "parameters must be an array or an object".to_owned(),
+            ))
+        }
+    }
+
+    fn get(&self, placeholder: &str) -> Result<&Value, ParserError> {
+        match placeholder.parse::<usize>() {
+            Ok(index) => self.get_positional(index - 1),
+            Err(_) => self.get_named(placeholder),
+        }
+    }
+
+    fn get_positional(&self, index: usize) -> Result<&Value, ParserError> {
+        match self {
+            Params::Positional(values) => values.get(index).ok_or_else(|| {
+                ParserError::ParserError(format!("missing parameter: ${}", index + 1))
+            }),
+            Params::Named(_) => Err(ParserError::ParserError(
+                "positional placeholder used with named parameters".to_owned(),
+            )),
+        }
+    }
+
+    fn get_named(&self, name: &str) -> Result<&Value, ParserError> {
+        match self {
+            Params::Named(values) => values
+                .get(name)
+                .ok_or_else(|| ParserError::ParserError(format!("missing parameter: ${}", name))),
+            Params::Positional(_) => Err(ParserError::ParserError(
+                "named placeholder used with positional parameters".to_owned(),
+            )),
+        }
+    }
+}
+
+fn convert_param(param: &JsValue) -> Result<Value, ParserError> {
+    if param.is_null() || param.is_undefined() {
+        return Ok(Value::Empty);
+    }
+
+    if let Some(v) = param.as_bool() {
+        return Ok(Value::Bool(v));
+    }
+
+    if let Some(v) = param.as_string() {
+        return Ok(Value::Str(v));
+    }
+
+    match param.as_f64() {
+        Some(v) if v.is_finite() && v.fract() == 0.0 && v.abs() < i64::MAX as f64 => {
+            Ok(Value::I64(v as i64))
+        }
+        Some(v) if v.is_finite() => Ok(Value::F64(v)),
+        _ => Err(ParserError::ParserError(format!(
+            "unsupported parameter: {:?}",
+            param
+        ))),
+    }
+}
+
+fn convert_value(value: &Value) -> Expr {
+    use Value::*;
+
+    match value {
+        Bool(v) | OptBool(Some(v)) => Expr::Value(AstValue::Boolean(*v)),
+        I64(v) | OptI64(Some(v)) => Expr::Value(AstValue::Number(v.to_string())),
+        F64(v) | OptF64(Some(v)) => Expr::Value(AstValue::Number(v.to_string())),
+        // text literals are double quoted in GlueSQL
+        Str(v) | OptStr(Some(v)) => Expr::Identifier(Ident {
+            value: v.to_owned(),
+            quote_style: Some('"'),
+        }),
+        OptBool(None) | OptI64(None) | OptF64(None) | OptStr(None) | Empty => {
+            Expr::Value(AstValue::Null)
+        }
+    }
+}
+
+fn mark_placeholder<I>(tokens: &mut Peekable<I>) -> Result<Token, ParserError>
+where
+    I: Iterator<Item = (Location, Token)>,
+{
+    let placeholder = match tokens.peek().map(|(_, token)| token) {
+        Some(Token::Number(index)) => match index.parse::<usize>() {
+            Ok(index) if index > 0 => index.to_string(),
+            _ => {
+                return Err(ParserError::ParserError(format!(
+                    "invalid placeholder: ${}",
+                    index
+                )));
+            }
+        },
+        Some(Token::Word(word)) if word.quote_style.is_none() => word.value.to_owned(),
+        _ => return Ok(Token::Char('$')),
+    };
+
+    tokens.next();
+
+    Ok(Token::make_word(&placeholder, Some(MARKER)))
+}
+
+/// Number of chars `token` takes at the start of `source`.
+/// Tokens print as their source text, except string literals whose escaped quotes were unescaped
+/// and `\r\n` newlines.
+fn source_len(token: &Token, source: &str) -> usize {
+    match token {
+        Token::SingleQuotedString(_)
+        | Token::NationalStringLiteral(_)
+        | Token::HexStringLiteral(_) => {
+            let mut chars = source
+                .chars()
+                .enumerate()
+                .skip_while(|(_, c)| *c != '\'')
+                .skip(1)
+                .peekable();
+
+            while let Some((i, c)) = chars.next() {
+                if c != '\'' {
+                    continue;
+                }
+
+                match chars.peek() {
+                    Some((_, '\'')) => {
+                        chars.next();
+                    }
+                    _ => return i + 1,
+                }
+            }
+
+            source.chars().count()
+        }
+        Token::Whitespace(Whitespace::Newline) if source.starts_with("\r\n") => 2,
+        token => token.to_string().chars().count(),
+    }
+}
+
+/// Pairs every token with where it starts in `sql`, which it was tokenized from.
+fn locate_tokens(sql: &str, tokens: Vec<Token>) -> Vec<(Location, Token)> {
+    let mut location = Location { line: 1, column: 1 };
+    let mut located = Vec::with_capacity(tokens.len() + 1);
+    let mut source = sql.chars();
+
+    for token in tokens {
+        let start = location;
+        let len = source_len(&token, source.as_str());
+        let text = source.by_ref().take(len).collect::<String>();
+
+        location = match token {
+            Token::Whitespace(Whitespace::Newline) => Location {
+                line: location.line + 1,
+                column: 1,
+            },
+            _ => text.chars().fold(location, |location, c| match c {
+                '\n' => Location {
+                    line: location.line + 1,
+                    column: 1,
+                },
+                _ => Location {
+                    column: location.column + 1,
+                    ..location
+                },
+            }),
+        };
+
+        located.push((start, token));
+    }
+
+    located.push((location, Token::EOF));
+    located
+}
+
+/// Finds where `parser` stopped, using the location of the non-whitespace tokens it was given.
+/// The failing token is either the one it stopped at or the one it just consumed.
+fn locate_error(parser: &mut Parser, locations: &[Location], error: &ParserError) -> Location {
+    let found = format!("found: {}", parser.peek_token());
+    let mut remaining = 0;
+
+    while parser.next_token() != Token::EOF {
+        remaining += 1;
+    }
+
+    let index = locations.len() - 1 - remaining;
+    let index = match error {
+        ParserError::ParserError(message) if message.ends_with(&found) => index,
+        _ => index.saturating_sub(1),
+    };
+
+    locations[index]
+}
+
+/// Parses `sql` which has no placeholders.
+pub fn parse(sql: &str) -> Result<Vec<Query>, GlueError> {
+    Prepared::new(sql)?.bind(&Params::Positional(vec![]))
+}
+
+/// SQL parsed once with its placeholders left in, ready to be bound to different values.
+pub struct Prepared {
+    statements: Vec<Statement>,
+    placeholders: usize,
+}
+
+impl Prepared {
+    pub fn new(sql: &str) -> Result<Self, GlueError> {
+        let dialect = GenericDialect {};
+        let tokens = Tokenizer::new(&dialect, sql).tokenize()?;
+
+        let mut marked = Vec::with_capacity(tokens.len());
+        let mut locations = vec![];
+        let mut tokens = locate_tokens(sql, tokens).into_iter().peekable();
+        let mut position = 0;
+        let mut placeholders = 0;
+
+        while let Some((location, token)) = tokens.next() {
+            let token = match token {
+                Token::EOF => {
+                    locations.push(location);
+                    break;
+                }
+                Token::Char('?') => {
+                    position += 1;
+
+                    Token::make_word(&position.to_string(), Some(MARKER))
+                }
+                Token::Char('$') => mark_placeholder(&mut tokens)
+                    .map_err(|error| GlueError::from(error).located(location))?,
+                token => token,
+            };
+
+            // `""` escapes a double quote, where the tokenizer ends one quoted word and starts
+            // another
+            if let (Token::Word(word), Some(Token::Word(previous))) = (&token, marked.last_mut()) {
+                if word.quote_style == Some('"') && previous.quote_style == Some('"') {
+                    previous.value = format!("{}\"{}", previous.value, word.value);
+
+                    continue;
+                }
+            }
+
+            match &token {
+                Token::Whitespace(_) => {}
+                Token::Word(word) if word.quote_style == Some(MARKER) => {
+                    placeholders += 1;
+                    locations.push(location);
+                }
+                _ => locations.push(location),
+            }
+
+            marked.push(token);
+        }
+
+        let mut parser = Parser::new(marked);
+        let mut statements = vec![];
+        let mut expecting_statement_delimiter = false;
+
+        loop {
+            while parser.consume_token(&Token::SemiColon) {
+                expecting_statement_delimiter = false;
+            }
+
+            let statement = match parser.peek_token() {
+                Token::EOF => break,
+                token if expecting_statement_delimiter => Err(ParserError::ParserError(format!(
+                    "Expected end of statement, found: {}",
+                    token
+                ))),
+                _ => parser.parse_statement(),
+            };
+
+            match statement {
+                Ok(statement) => statements.push(statement),
+                Err(error) => {
+                    // trailing tokens belong to the statement parsed last
+                    let index = match expecting_statement_delimiter {
+                        true => statements.len() - 1,
+                        false => statements.len(),
+                    };
+                    let location = locate_error(&mut parser, &locations, &error);
+                    let error = GlueError::from(error).at(index).located(location);
+
+                    return Err(error);
+                }
+            }
+
+            expecting_statement_delimiter = true;
+        }
+
+        Ok(Self {
+            statements,
+            placeholders,
+        })
+    }
+
+    /// Replaces each placeholder with the literal of its bound value, so values are never
+    /// spliced into the SQL text.
+    pub fn bind(&self, params: &Params) -> Result<Vec<Query>, GlueError> {
+        let mut binder = Binder { params, bound: 0 };
+        let mut queries = Vec::with_capacity(self.statements.len());
+
+        for (i, statement) in self.statements.iter().enumerate() {
+            let mut statement = statement.clone();
+
+            binder
+                .statement(&mut statement)
+                .map_err(|error| GlueError::from(error).at(i))?;
+            queries.push(Query(statement));
+        }
+
+        if binder.bound != self.placeholders {
+            let error = ParserError::ParserError(
+                "placeholders are only supported in place of values".to_owned(),
+            );
+
+            return Err(error.into());
+        }
+
+        Ok(queries)
+    }
+}
+
+struct Binder<'a> {
+    params: &'a Params,
+    bound: usize,
+}
+
+impl<'a> Binder<'a> {
+    fn statement(&mut self, statement: &mut Statement) -> Result<(), ParserError> {
+        match statement {
+            Statement::Query(query) => self.query(query),
+            Statement::Insert { source, .. } => self.query(source),
+            Statement::Update {
+                assignments,
+                selection,
+                ..
+            } => {
+                for assignment in assignments.iter_mut() {
+                    self.expr(&mut assignment.value)?;
+                }
+
+                self.exprs(selection.iter_mut())
+            }
+            Statement::Delete { selection, .. } => self.exprs(selection.iter_mut()),
+            _ => Ok(()),
+        }
+    }
+
+    fn query(&mut self, query: &mut AstQuery) -> Result<(), ParserError> {
+        for cte in query.ctes.iter_mut() {
+            self.query(&mut cte.query)?;
+        }
+
+        self.set_expr(&mut query.body)?;
+        self.exprs(query.order_by.iter_mut().map(|order_by| &mut order_by.expr))?;
+        self.exprs(query.limit.iter_mut())?;
+        self.exprs(query.offset.iter_mut().map(|offset| &mut offset.value))
+    }
+
+    fn set_expr(&mut self, set_expr: &mut SetExpr) -> Result<(), ParserError> {
+        match set_expr {
+            SetExpr::Select(select) => self.select(select),
+            SetExpr::Query(query) => self.query(query),
+            SetExpr::SetOperation { left, right, .. } => {
+                self.set_expr(left)?;
+                self.set_expr(right)
+            }
+            SetExpr::Values(values) => self.exprs(values.0.iter_mut().flatten()),
+        }
+    }
+
+    fn select(&mut self, select: &mut Select) -> Result<(), ParserError> {
+        for item in select.projection.iter_mut() {
+            match item {
+                SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                    self.expr(expr)?;
+                }
+                _ => {}
+            }
+        }
+
+        for table in select.from.iter_mut() {
+            self.table_with_joins(table)?;
+        }
+
+        self.exprs(select.selection.iter_mut())?;
+        self.exprs(select.group_by.iter_mut())?;
+        self.exprs(select.having.iter_mut())
+    }
+
+    fn table_with_joins(&mut self, table: &mut TableWithJoins) -> Result<(), ParserError> {
+        self.table_factor(&mut table.relation)?;
+
+        for Join {
+            relation,
+            join_operator,
+        } in table.joins.iter_mut()
+        {
+            self.table_factor(relation)?;
+
+            match join_operator {
+                JoinOperator::Inner(JoinConstraint::On(expr))
+                | JoinOperator::LeftOuter(JoinConstraint::On(expr))
+                | JoinOperator::RightOuter(JoinConstraint::On(expr))
+                | JoinOperator::FullOuter(JoinConstraint::On(expr)) => self.expr(expr)?,
+                _ => {}
+            }
+        }
+
+        Ok(())
+    }
+
+    fn table_factor(&mut self, table_factor: &mut TableFactor) -> Result<(), ParserError> {
+        match table_factor {
+            TableFactor::Table { args, .. } => self.exprs(args.iter_mut()),
+            TableFactor::Derived { subquery, .. } => self.query(subquery),
+            TableFactor::NestedJoin(table) => self.table_with_joins(table),
+        }
+    }
+
+    fn exprs<'b, I>(&mut self, exprs: I) -> Result<(), ParserError>
+    where
+        I: Iterator<Item = &'b mut Expr>,
+    {
+        for expr in exprs {
+            self.expr(expr)?;
+        }
+
+        Ok(())
+    }
+
+    fn expr(&mut self, expr: &mut Expr) -> Result<(), ParserError> {
+        match expr {
+            Expr::Identifier(Ident {
+                value,
+                quote_style: Some(MARKER),
+            }) => {
+                *expr = convert_value(self.params.get(value)?);
+                self.bound += 1;
+
+                Ok(())
+            }
+            Expr::IsNull(expr)
+            | Expr::IsNotNull(expr)
+            | Expr::UnaryOp { expr, .. }
+            | Expr::Cast { expr, .. }
+            | Expr::Extract { expr, .. }
+            | Expr::Collate { expr, .. }
+            | Expr::Nested(expr) => self.expr(expr),
+            Expr::InList { expr, list, .. } => {
+                self.expr(expr)?;
+                self.exprs(list.iter_mut())
+            }
+            Expr::InSubquery { expr, subquery, .. } => {
+                self.expr(expr)?;
+                self.query(subquery)
+            }
+            Expr::Between {
+                expr, low, high, ..
+            } => {
+                self.expr(expr)?;
+                self.expr(low)?;
+                self.expr(high)
+            }
+            Expr::BinaryOp { left, right, .. } => {
+                self.expr(left)?;
+                self.expr(right)
+            }
+            Expr::Function(function) => self.exprs(function.args.iter_mut()),
+            Expr::Case {
+                operand,
+                conditions,
+                results,
+                else_result,
+            } => {
+                self.exprs(operand.iter_mut().map(AsMut::as_mut))?;
+                self.exprs(conditions.iter_mut())?;
+                self.exprs(results.iter_mut())?;
+                self.exprs(else_result.iter_mut().map(AsMut::as_mut))
+            }
+            Expr::Exists(query) | Expr::Subquery(query) => self.query(query),
+            Expr::ListAgg(list_agg) => self.expr(&mut list_agg.expr),
+            _ => Ok(()),
+        }
+    }
+}
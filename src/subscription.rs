@@ -0,0 +1,117 @@
+use js_sys::{Function, Object, Reflect};
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::rc::Rc;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use web_sys::{EventTarget, StorageEvent};
+
+use crate::web_storage::parse_change;
+
+/// Handle of a subscription, which stops calling its callback once `unsubscribe` is called.
+/// Freeing it, which the garbage collector may do for a handle nobody keeps, does not stop the
+/// subscription.
+#[wasm_bindgen]
+pub struct Subscription {
+    cancel: Option<Box<dyn FnOnce()>>,
+}
+
+impl Subscription {
+    pub fn new(cancel: impl FnOnce() + 'static) -> Self {
+        Self {
+            cancel: Some(Box::new(cancel)),
+        }
+    }
+}
+
+#[wasm_bindgen]
+impl Subscription {
+    pub fn unsubscribe(&mut self) {
+        if let Some(cancel) = self.cancel.take() {
+            cancel();
+        }
+    }
+}
+
+/// Event listeners of a `Glue`, each kept with the function which removes it until its
+/// subscription is cancelled.
+#[derive(Default)]
+pub struct Listeners {
+    next_id: u32,
+    listeners: HashMap<u32, Box<dyn FnOnce()>>,
+}
+
+impl Listeners {
+    pub fn add(&mut self, remove: impl FnOnce() + 'static) -> u32 {
+        let id = self.next_id;
+        self.next_id += 1;
+
+        self.listeners.insert(id, Box::new(remove));
+
+        id
+    }
+
+    pub fn remove(&mut self, id: u32) {
+        if let Some(remove) = self.listeners.remove(&id) {
+            remove();
+        }
+    }
+}
+
+/// Calls `callback` with `{ table, kind }` for every `storage` event of `localStorage` which
+/// another tab fires by writing to the tables of `namespace`.
+/// The listener is kept in `listeners` until the subscription is cancelled.
+pub fn listen_storage_events(
+    listeners: Rc<RefCell<Listeners>>,
+    namespace: String,
+    callback: Function,
+) -> Result<Subscription, JsValue> {
+    let global = js_sys::global();
+    let local_storage = Reflect::get(&global, &JsValue::from_str("localStorage"))?;
+
+    let listener = Closure::wrap(Box::new(move |event: StorageEvent| {
+        // sessionStorage fires the same events for frames of the same tab
+        let area = event.storage_area().map(JsValue::from);
+        if area.as_ref() != Some(&local_storage) {
+            return Ok(());
+        }
+
+        let key = match event.key() {
+            Some(key) => key,
+            None => return Ok(()),
+        };
+        let old_value = event.old_value();
+        let new_value = event.new_value();
+
+        let (table_name, kind) =
+            match parse_change(&namespace, &key, old_value.as_deref(), new_value.as_deref()) {
+                Some(change) => change,
+                None => return Ok(()),
+            };
+
+        let change = Object::new();
+        Reflect::set(
+            &change,
+            &JsValue::from_str("table"),
+            &JsValue::from_str(&table_name),
+        )?;
+        Reflect::set(
+            &change,
+            &JsValue::from_str("kind"),
+            &JsValue::from_str(kind),
+        )?;
+
+        callback.call1(&JsValue::NULL, &change).map(|_| ())
+    })
+        as Box<dyn FnMut(StorageEvent) -> Result<(), JsValue>>);
+
+    let target: EventTarget = global.unchecked_into();
+    target.add_event_listener_with_callback("storage", listener.as_ref().unchecked_ref())?;
+
+    let id = listeners.borrow_mut().add(move || {
+        let _ = target
+            .remove_event_listener_with_callback("storage", listener.as_ref().unchecked_ref());
+    });
+
+    Ok(Subscription::new(move || listeners.borrow_mut().remove(id)))
+}
@@ -0,0 +1,40 @@
+use async_trait::async_trait;
+use std::fmt;
+
+use gluesql_core::{Error, MutResult};
+
+#[derive(Debug)]
+pub enum TransactionError {
+    AlreadyStarted,
+    NotStarted,
+}
+
+impl fmt::Display for TransactionError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TransactionError::AlreadyStarted => write!(f, "transaction already started"),
+            TransactionError::NotStarted => write!(f, "no transaction in progress"),
+        }
+    }
+}
+
+impl std::error::Error for TransactionError {}
+
+impl From<TransactionError> for Error {
+    fn from(e: TransactionError) -> Self {
+        Error::Storage(Box::new(e))
+    }
+}
+
+/// Keeps writes made after `begin` aside until `commit`, or throws them away on `rollback`.
+#[async_trait(?Send)]
+pub trait Transaction
+where
+    Self: Sized,
+{
+    async fn begin(self) -> MutResult<Self, ()>;
+
+    async fn commit(self) -> MutResult<Self, ()>;
+
+    async fn rollback(self) -> MutResult<Self, ()>;
+}
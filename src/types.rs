@@ -0,0 +1,111 @@
+use std::fmt::Debug;
+
+use gluesql_core::parser::ast::{
+    ColumnDef, DataType, Expr, Ident, SelectItem, SetExpr, Statement, TableFactor,
+};
+use gluesql_core::{Result, Store};
+
+struct Table {
+    alias: String,
+    column_defs: Vec<ColumnDef>,
+}
+
+impl Table {
+    fn find(&self, column_name: &str) -> Option<DataType> {
+        self.column_defs
+            .iter()
+            .find(|ColumnDef { name, .. }| name.value == column_name)
+            .map(|ColumnDef { data_type, .. }| data_type.clone())
+    }
+
+    fn data_types(&self) -> impl Iterator<Item = Option<DataType>> + '_ {
+        self.column_defs
+            .iter()
+            .map(|ColumnDef { data_type, .. }| Some(data_type.clone()))
+    }
+}
+
+/// Resolves the declared data type of each selected column from the table schemas.
+/// Computed columns, such as `id + 1` or `COUNT(*)`, resolve to `None`.
+pub async fn fetch_types<T, U>(storage: &U, statement: &Statement) -> Result<Vec<Option<DataType>>>
+where
+    T: 'static + Debug,
+    U: Store<T>,
+{
+    let select = match statement {
+        Statement::Query(query) => match &query.body {
+            SetExpr::Select(select) => select,
+            _ => return Ok(vec![]),
+        },
+        _ => return Ok(vec![]),
+    };
+
+    let relations = select.from.iter().flat_map(|table_with_joins| {
+        let joins = table_with_joins.joins.iter().map(|join| &join.relation);
+
+        std::iter::once(&table_with_joins.relation).chain(joins)
+    });
+
+    let mut tables = vec![];
+
+    for relation in relations {
+        let (name, alias) = match relation {
+            TableFactor::Table { name, alias, .. } => (name, alias),
+            _ => return Ok(vec![]),
+        };
+
+        let table_name = match name.0.last() {
+            Some(Ident { value, .. }) => value,
+            None => return Ok(vec![]),
+        };
+
+        let column_defs = storage
+            .fetch_schema(table_name)
+            .await?
+            .map(|schema| schema.column_defs)
+            .unwrap_or_default();
+
+        let alias = alias
+            .as_ref()
+            .map(|alias| alias.name.value.to_owned())
+            .unwrap_or_else(|| table_name.to_owned());
+
+        tables.push(Table { alias, column_defs });
+    }
+
+    let find_table = |alias: &str| tables.iter().find(|table| table.alias == alias);
+
+    let data_types = select
+        .projection
+        .iter()
+        .flat_map(|item| -> Vec<Option<DataType>> {
+            match item {
+                SelectItem::Wildcard => tables.iter().flat_map(Table::data_types).collect(),
+                SelectItem::QualifiedWildcard(target) => target
+                    .0
+                    .last()
+                    .and_then(|Ident { value, .. }| find_table(value))
+                    .map(|table| table.data_types().collect())
+                    .unwrap_or_default(),
+                SelectItem::UnnamedExpr(expr) | SelectItem::ExprWithAlias { expr, .. } => {
+                    let data_type = match expr {
+                        // double quoted identifiers are text literals
+                        Expr::Identifier(Ident {
+                            value,
+                            quote_style: None,
+                        }) => tables.iter().find_map(|table| table.find(value)),
+                        Expr::CompoundIdentifier(idents) if idents.len() == 2 => {
+                            find_table(&idents[0].value)
+                                .and_then(|table| table.find(&idents[1].value))
+                        }
+                        _ => None,
+                    };
+
+                    vec![data_type]
+                }
+            }
+        })
+        .collect();
+
+    Ok(data_types)
+}
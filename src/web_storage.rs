@@ -1,5 +1,19 @@
 use async_trait::async_trait;
+use futures::future::{select, Either, LocalBoxFuture};
+use js_sys::{Array, Function, Object, Promise, Reflect};
 use serde::{Deserialize, Serialize};
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
+use std::fmt;
+use std::rc::Rc;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::{
+    Blob, DomException, Event, FileSystemDirectoryHandle, FileSystemFileHandle,
+    FileSystemGetDirectoryOptions, FileSystemGetFileOptions, FileSystemWritableFileStream,
+    IdbDatabase, IdbFactory, IdbKeyRange, IdbObjectStore, IdbRequest, IdbTransaction,
+    IdbTransactionMode, StorageManager,
+};
 
 use gluesql_core::parser::ast::{ColumnDef, ColumnOption, ColumnOptionDef, Value as AstValue};
 use gluesql_core::{
@@ -9,6 +23,26 @@ use gluesql_core::{
 
 use wasm_bindgen::prelude::*;
 
+use crate::backup::{Backupable, TableBackup};
+use crate::catalog::Catalog;
+use crate::transaction::{Transaction, TransactionError};
+
+const PREFIX: &str = "__gluesql-v0.3__";
+const LEGACY_PREFIX: &str = "__gluesql-v0.2__";
+
+/// Pending writes of an open transaction, `None` marks a removed key.
+type Staged = HashMap<String, Option<String>>;
+
+enum Journal {
+    /// Keys whose original values are saved under `{namespace}/journal/`.
+    Persisted(HashSet<String>),
+    /// Staged writes of the open transaction at the time the journal started.
+    Staged(Staged),
+    /// Like `Persisted`, once a `COMMIT` has flushed the transaction which was open when the
+    /// journal started, whose staged writes a rollback brings back.
+    Committed(HashSet<String>, Staged),
+}
+
 macro_rules! try_into {
     ($expr: expr) => {
         $expr.map_err(|e| Error::Storage(Box::new(e)))?
@@ -36,22 +70,72 @@ macro_rules! try_self {
     };
 }
 
+const KEY_KINDS: [&str; 5] = ["schema", "id", "index", "data", "journal"];
+
+/// Namespace of a key without its prefix, e.g. `{namespace}/data/{table_name}/{id}`.
+/// The namespace ends at the first `/{kind}/`, so table names may be any of the kinds.
+fn parse_namespace(key: &str) -> Option<&str> {
+    key.match_indices('/')
+        .map(|(index, _)| index)
+        .filter(|index| *index > 0)
+        .find(|index| {
+            let rest = &key[index + 1..];
+
+            KEY_KINDS.iter().any(|kind| {
+                rest.strip_prefix(kind)
+                    .is_some_and(|rest| rest.starts_with('/'))
+            })
+        })
+        .map(|index| &key[..index])
+}
+
+/// Table name and kind of a change to a key of the namespace, e.g. `("Item", "insert")`.
+/// Keys other than schemas and rows are not changes to report.
+pub fn parse_change(
+    namespace: &str,
+    key: &str,
+    old_value: Option<&str>,
+    new_value: Option<&str>,
+) -> Option<(String, &'static str)> {
+    let prefix = format!("{}/{}/", PREFIX, namespace);
+    let (kind, rest) = key.strip_prefix(&prefix)?.split_once('/')?;
+
+    let (created, changed, removed) = match kind {
+        "schema" => ("create", "alter", "drop"),
+        "data" => ("insert", "update", "delete"),
+        _ => return None,
+    };
+
+    let kind = match (old_value, new_value) {
+        (None, Some(_)) => created,
+        (Some(_), Some(_)) => changed,
+        (Some(_), None) => removed,
+        (None, None) => return None,
+    };
+    let table_name = rest.split('/').next()?;
+
+    Some((table_name.to_owned(), kind))
+}
+
 macro_rules! generate_storage_code {
     ($type: tt, $Storage: ident, $StorageKey: ident, $get_item: ident, $set_item: ident, $remove_item: ident) => {
         #[wasm_bindgen]
         extern "C" {
-            #[wasm_bindgen(js_namespace = $type, js_name = getItem)]
-            fn $get_item(k: &str) -> JsValue;
+            #[wasm_bindgen(catch, js_namespace = $type, js_name = getItem)]
+            fn $get_item(k: &str) -> std::result::Result<JsValue, JsValue>;
 
-            #[wasm_bindgen(js_namespace = $type, js_name = setItem)]
-            fn $set_item(k: &str, v: &str);
+            #[wasm_bindgen(catch, js_namespace = $type, js_name = setItem)]
+            fn $set_item(k: &str, v: &str) -> std::result::Result<(), JsValue>;
 
-            #[wasm_bindgen(js_namespace = $type, js_name = removeItem)]
-            fn $remove_item(k: &str);
+            #[wasm_bindgen(catch, js_namespace = $type, js_name = removeItem)]
+            fn $remove_item(k: &str) -> std::result::Result<(), JsValue>;
         }
 
         pub struct $Storage {
             namespace: String,
+            initialized: Cell<bool>,
+            journal: RefCell<Option<Journal>>,
+            staged: RefCell<Option<Staged>>,
         }
 
         #[derive(Clone, Debug, Serialize, Deserialize)]
@@ -62,29 +146,586 @@ macro_rules! generate_storage_code {
 
         impl $Storage {
             pub fn new(namespace: String) -> Result<Self> {
-                Ok(Self { namespace })
+                Ok(Self {
+                    namespace,
+                    initialized: Cell::new(false),
+                    journal: RefCell::new(None),
+                    staged: RefCell::new(None),
+                })
+            }
+
+            async fn get_item(&self, key: &str) -> Result<Option<String>> {
+                $get_item(key)
+                    .map(|value| value.as_string())
+                    .map_err(js_error)
+            }
+
+            async fn set_item(&self, key: &str, value: &str) -> Result<()> {
+                $set_item(key, value).map_err(js_error)
+            }
+
+            async fn remove_item(&self, key: &str) -> Result<()> {
+                $remove_item(key).map_err(js_error)
+            }
+
+            /// Writes are done once they return.
+            pub async fn complete(&self) -> Result<()> {
+                Ok(())
+            }
+
+            async fn scan_keys(&self, prefix: &str) -> Result<Vec<String>> {
+                let storage =
+                    Reflect::get(&js_sys::global(), &JsValue::from_str(stringify!($type)))
+                        .map_err(js_error)?;
+                let keys = Object::keys(storage.unchecked_ref::<Object>())
+                    .iter()
+                    .filter_map(|key| key.as_string())
+                    .filter(|key| key.starts_with(prefix))
+                    .collect();
+
+                Ok(keys)
+            }
+        }
+
+        impl_storage!($Storage, $StorageKey);
+    };
+}
+
+macro_rules! impl_storage {
+    ($Storage: ident, $StorageKey: ident) => {
+        impl $Storage {
+            pub fn namespace(&self) -> &str {
+                &self.namespace
+            }
+
+            /// Whether `begin` was called without a `commit` or `rollback` yet.
+            pub fn in_transaction(&self) -> bool {
+                self.staged.borrow().is_some()
             }
 
             fn get_id_prefix(&self, table_name: &str) -> String {
-                format!("__gluesql-v0.2__/{}/id/{}", self.namespace, table_name)
+                format!("{}/{}/id/{}", PREFIX, self.namespace, table_name)
             }
 
             fn get_schema_prefix(&self, table_name: &str) -> String {
-                format!("__gluesql-v0.2__/{}/schema/{}", self.namespace, table_name)
+                format!("{}/{}/schema/{}", PREFIX, self.namespace, table_name)
+            }
+
+            fn get_index_prefix(&self, table_name: &str) -> String {
+                format!("{}/{}/index/{}", PREFIX, self.namespace, table_name)
+            }
+
+            fn get_data_prefix(&self, table_name: &str, id: u64) -> String {
+                format!("{}/{}/data/{}/{}", PREFIX, self.namespace, table_name, id)
+            }
+
+            async fn fetch_ids(&self, table_name: &str) -> Result<Vec<u64>> {
+                let prefix = self.get_index_prefix(table_name);
+
+                let ids = match self.get(&prefix).await? {
+                    Some(ids) => try_into!(serde_json::from_str(&ids)),
+                    None => vec![],
+                };
+
+                Ok(ids)
+            }
+
+            async fn fetch_rows(&self, table_name: &str) -> Result<Vec<(u64, Row)>> {
+                let mut items = vec![];
+
+                for id in self.fetch_ids(table_name).await? {
+                    let prefix = self.get_data_prefix(table_name, id);
+
+                    if let Some(row) = self.get(&prefix).await? {
+                        let row: Row = try_into!(serde_json::from_str(&row));
+
+                        items.push((id, row));
+                    }
+                }
+
+                Ok(items)
+            }
+
+            /// Last id generated for the table, or 0 if there is none.
+            async fn fetch_id(&self, table_name: &str) -> Result<u64> {
+                let prefix = self.get_id_prefix(table_name);
+
+                let id = match self.get(&prefix).await? {
+                    Some(key) => {
+                        let $StorageKey { id, .. } = try_into!(serde_json::from_str(&key));
+
+                        id
+                    }
+                    None => 0,
+                };
+
+                Ok(id)
+            }
+
+            async fn insert_rows(&self, table_name: &str, items: &[(u64, Row)]) -> Result<()> {
+                for (id, row) in items {
+                    let prefix = self.get_data_prefix(table_name, *id);
+                    let row = try_into!(serde_json::to_string(row));
+
+                    self.set(&prefix, &row).await?;
+                }
+
+                Ok(())
+            }
+
+            async fn init(&self) -> Result<()> {
+                if self.initialized.get() {
+                    return Ok(());
+                }
+
+                self.migrate().await?;
+                self.restore_journal().await?;
+                self.initialized.set(true);
+
+                Ok(())
+            }
+
+            /// Converts tables written by the v0.2 layout, which kept a whole table as a single
+            /// `Vec<(u64, Row)>` blob, into the current row-per-key layout.
+            async fn migrate(&self) -> Result<()> {
+                let legacy_prefix = format!("{}/{}/", LEGACY_PREFIX, self.namespace);
+
+                for key in self.scan_keys(&legacy_prefix).await? {
+                    let value = match self.get_item(&key).await? {
+                        Some(value) => value,
+                        None => continue,
+                    };
+
+                    match key[legacy_prefix.len()..].split_once('/') {
+                        Some(("id", table_name)) => {
+                            self.set_item(&self.get_id_prefix(table_name), &value)
+                                .await?;
+                        }
+                        Some(("schema", table_name)) => {
+                            self.set_item(&self.get_schema_prefix(table_name), &value)
+                                .await?;
+                        }
+                        Some(("data", table_name)) => {
+                            let items: Vec<(u64, Row)> = try_into!(serde_json::from_str(&value));
+                            let ids = items.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+                            let ids = try_into!(serde_json::to_string(&ids));
+
+                            self.insert_rows(table_name, &items).await?;
+                            self.set_item(&self.get_index_prefix(table_name), &ids)
+                                .await?;
+                        }
+                        _ => continue,
+                    }
+
+                    self.remove_item(&key).await?;
+                }
+
+                Ok(())
+            }
+
+            fn get_journal_prefix(&self) -> String {
+                format!("{}/{}/journal/", PREFIX, self.namespace)
+            }
+
+            /// Saves the original value of `key` to the journal before its first write, so the
+            /// batch can be undone even if the page is closed before it finishes.
+            async fn record(&self, key: &str) -> Result<()> {
+                let index = match self.journal.borrow_mut().as_mut() {
+                    Some(Journal::Persisted(keys)) | Some(Journal::Committed(keys, _))
+                        if !keys.contains(key) =>
+                    {
+                        keys.insert(key.to_owned());
+                        keys.len()
+                    }
+                    _ => return Ok(()),
+                };
+
+                let original = self.get_item(key).await?;
+                let entry = try_into!(serde_json::to_string(&(key, original)));
+                let prefix = format!("{}{}", self.get_journal_prefix(), index);
+
+                self.set_item(&prefix, &entry).await
+            }
+
+            async fn get(&self, key: &str) -> Result<Option<String>> {
+                if let Some(value) = self
+                    .staged
+                    .borrow()
+                    .as_ref()
+                    .and_then(|staged| staged.get(key))
+                {
+                    return Ok(value.clone());
+                }
+
+                self.get_item(key).await
+            }
+
+            async fn set(&self, key: &str, value: &str) -> Result<()> {
+                if let Some(staged) = self.staged.borrow_mut().as_mut() {
+                    staged.insert(key.to_owned(), Some(value.to_owned()));
+
+                    return Ok(());
+                }
+
+                self.record(key).await?;
+                self.set_item(key, value).await
+            }
+
+            async fn remove(&self, key: &str) -> Result<()> {
+                if let Some(staged) = self.staged.borrow_mut().as_mut() {
+                    staged.insert(key.to_owned(), None);
+
+                    return Ok(());
+                }
+
+                self.record(key).await?;
+                self.remove_item(key).await
+            }
+
+            async fn restore_journal(&self) -> Result<()> {
+                for prefix in self.scan_keys(&self.get_journal_prefix()).await? {
+                    if let Some(entry) = self.get_item(&prefix).await? {
+                        let (key, original): (String, Option<String>) =
+                            try_into!(serde_json::from_str(&entry));
+
+                        match original {
+                            Some(value) => self.set_item(&key, &value).await?,
+                            None => self.remove_item(&key).await?,
+                        }
+                    }
+
+                    self.remove_item(&prefix).await?;
+                }
+
+                Ok(())
+            }
+
+            /// Starts recording every key written from now on, until `commit_journal` or
+            /// `rollback_journal` is called.
+            /// Inside a transaction nothing is persisted yet, so only the staged writes are kept.
+            pub async fn begin_journal(&self) -> Result<()> {
+                self.init().await?;
+
+                let journal = match self.staged.borrow().clone() {
+                    Some(staged) => Journal::Staged(staged),
+                    None => Journal::Persisted(HashSet::new()),
+                };
+                self.journal.replace(Some(journal));
+
+                Ok(())
+            }
+
+            pub async fn commit_journal(&self) -> Result<()> {
+                if let Some(Journal::Staged(_)) = self.journal.replace(None) {
+                    return Ok(());
+                }
+
+                for prefix in self.scan_keys(&self.get_journal_prefix()).await? {
+                    self.remove_item(&prefix).await?;
+                }
+
+                Ok(())
+            }
+
+            pub async fn rollback_journal(&self) -> Result<()> {
+                match self.journal.replace(None) {
+                    Some(Journal::Staged(staged)) => {
+                        self.staged.replace(Some(staged));
+
+                        Ok(())
+                    }
+                    Some(Journal::Committed(_, staged)) => {
+                        self.restore_journal().await?;
+                        self.staged.replace(Some(staged));
+
+                        Ok(())
+                    }
+                    _ => {
+                        self.staged.replace(None);
+                        self.restore_journal().await
+                    }
+                }
+            }
+
+            async fn flush(&self, staged: Staged) -> Result<()> {
+                for (key, value) in staged {
+                    match value {
+                        Some(value) => self.set(&key, &value).await?,
+                        None => self.remove(&key).await?,
+                    }
+                }
+
+                Ok(())
+            }
+
+            /// Keys starting with `prefix`, including the ones staged by an open transaction.
+            async fn scan(&self, prefix: &str) -> Result<Vec<String>> {
+                let mut keys = self.scan_keys(prefix).await?;
+
+                if let Some(staged) = self.staged.borrow().as_ref() {
+                    keys.retain(|key| !matches!(staged.get(key), Some(None)));
+                    keys.extend(
+                        staged
+                            .iter()
+                            .filter(|(key, value)| value.is_some() && key.starts_with(prefix))
+                            .map(|(key, _)| key.to_owned()),
+                    );
+                }
+
+                keys.sort();
+                keys.dedup();
+
+                Ok(keys)
+            }
+
+            /// Keys of the current and legacy layouts, paired with the namespace they belong to.
+            async fn scan_namespaced_keys(&self) -> Result<Vec<(String, String)>> {
+                let mut keys = vec![];
+
+                for prefix in [PREFIX, LEGACY_PREFIX].iter() {
+                    let prefix = format!("{}/", prefix);
+
+                    for key in self.scan_keys(&prefix).await? {
+                        if let Some(namespace) = parse_namespace(&key[prefix.len()..]) {
+                            keys.push((namespace.to_owned(), key));
+                        }
+                    }
+                }
+
+                Ok(keys)
+            }
+
+            /// Estimated bytes taken by the keys and values of each table, counted as UTF-16
+            /// strings, sorted by table name.
+            pub async fn storage_usage(&self) -> Result<Vec<(String, usize)>> {
+                let prefix = format!("{}/{}/", PREFIX, self.namespace);
+                let mut usage = std::collections::BTreeMap::new();
+
+                for key in self.scan_keys(&prefix).await? {
+                    let table_name = match key[prefix.len()..].split('/').collect::<Vec<_>>()[..] {
+                        ["schema" | "id" | "index", table_name] | ["data", table_name, _] => {
+                            table_name.to_owned()
+                        }
+                        _ => continue,
+                    };
+                    let value = self.get_item(&key).await?.unwrap_or_default();
+                    let length = key.encode_utf16().count() + value.encode_utf16().count();
+
+                    *usage.entry(table_name).or_insert(0) += length * 2;
+                }
+
+                Ok(usage.into_iter().collect())
+            }
+
+            /// Namespaces which have any key in this storage, sorted.
+            pub async fn list_namespaces(&self) -> Result<Vec<String>> {
+                let mut namespaces = self
+                    .scan_namespaced_keys()
+                    .await?
+                    .into_iter()
+                    .map(|(namespace, _)| namespace)
+                    .collect::<Vec<_>>();
+
+                namespaces.sort();
+                namespaces.dedup();
+
+                Ok(namespaces)
+            }
+
+            /// Removes every key of the namespace, including the ones of the legacy layout.
+            pub async fn drop_namespace(&self) -> Result<()> {
+                for (namespace, key) in self.scan_namespaced_keys().await? {
+                    if namespace == self.namespace {
+                        self.remove_item(&key).await?;
+                    }
+                }
+
+                Ok(())
+            }
+
+            /// Copies the namespace to `target`, which must not exist yet.
+            pub async fn copy_namespace(&self, target: &str) -> Result<()> {
+                self.init().await?;
+
+                let keys = self.scan_namespaced_keys().await?;
+
+                if keys.iter().any(|(namespace, _)| namespace == target) {
+                    return Err(WebStorageError::NamespaceExists(target.to_owned()).into());
+                }
+
+                let source_prefix = format!("{}/{}/", PREFIX, self.namespace);
+                let target_prefix = format!("{}/{}/", PREFIX, target);
+
+                for (namespace, key) in keys {
+                    if namespace != self.namespace || !key.starts_with(&source_prefix) {
+                        continue;
+                    }
+
+                    if let Some(value) = self.get_item(&key).await? {
+                        let target_key =
+                            format!("{}{}", target_prefix, &key[source_prefix.len()..]);
+
+                        self.set_item(&target_key, &value).await?;
+                    }
+                }
+
+                Ok(())
+            }
+        }
+
+        #[async_trait(?Send)]
+        impl Catalog for $Storage {
+            async fn list_schemas(&self) -> Result<Vec<Schema>> {
+                self.init().await?;
+
+                let prefix = self.get_schema_prefix("");
+                let mut schemas = vec![];
+
+                for key in self.scan(&prefix).await? {
+                    if let Some(schema) = self.get(&key).await? {
+                        let schema: Schema = try_into!(serde_json::from_str(&schema));
+
+                        schemas.push(schema);
+                    }
+                }
+
+                schemas.sort_by(|a, b| a.table_name.cmp(&b.table_name));
+
+                Ok(schemas)
+            }
+        }
+
+        impl $Storage {
+            async fn write_table(&self, table: TableBackup) -> Result<()> {
+                let TableBackup { schema, id, rows } = table;
+                let table_name = &schema.table_name;
+
+                for id in self.fetch_ids(table_name).await? {
+                    self.remove(&self.get_data_prefix(table_name, id)).await?;
+                }
+
+                let ids = rows.iter().map(|(id, _)| *id).collect::<Vec<_>>();
+                let ids = try_into!(serde_json::to_string(&ids));
+                let key = $StorageKey {
+                    table_name: table_name.to_owned(),
+                    id: id.max(self.fetch_id(table_name).await?),
+                };
+                let key = try_into!(serde_json::to_string(&key));
+
+                self.insert_rows(table_name, &rows).await?;
+                self.set(&self.get_index_prefix(table_name), &ids).await?;
+                self.set(&self.get_id_prefix(table_name), &key).await?;
+
+                let schema_prefix = self.get_schema_prefix(table_name);
+                let schema = try_into!(serde_json::to_string(&schema));
+
+                self.set(&schema_prefix, &schema).await
+            }
+
+            async fn write_tables(&self, tables: Vec<TableBackup>) -> Result<()> {
+                for table in tables {
+                    self.write_table(table).await?;
+                }
+
+                Ok(())
+            }
+        }
+
+        #[async_trait(?Send)]
+        impl Backupable for $Storage {
+            async fn export_tables(&self) -> Result<Vec<TableBackup>> {
+                let mut tables = vec![];
+
+                for schema in self.list_schemas().await? {
+                    let table_name = &schema.table_name;
+                    let id = self.fetch_id(table_name).await?;
+                    let rows = self.fetch_rows(table_name).await?;
+
+                    tables.push(TableBackup { schema, id, rows });
+                }
+
+                Ok(tables)
+            }
+
+            async fn import_tables(self, tables: Vec<TableBackup>) -> MutResult<Self, ()> {
+                try_self!(self, self.begin_journal().await);
+
+                let result = match self.write_tables(tables).await {
+                    Ok(()) => self.commit_journal().await,
+                    Err(error) => self.rollback_journal().await.and(Err(error)),
+                };
+
+                try_self!(self, result);
+
+                Ok((self, ()))
+            }
+        }
+
+        #[async_trait(?Send)]
+        impl Transaction for $Storage {
+            async fn begin(self) -> MutResult<Self, ()> {
+                try_self!(self, self.init().await);
+
+                if self.staged.borrow().is_some() {
+                    return Err((self, TransactionError::AlreadyStarted.into()));
+                }
+
+                self.staged.replace(Some(HashMap::new()));
+
+                Ok((self, ()))
+            }
+
+            async fn commit(self) -> MutResult<Self, ()> {
+                let staged = match self.staged.replace(None) {
+                    Some(staged) => staged,
+                    None => {
+                        return Err((self, TransactionError::NotStarted.into()));
+                    }
+                };
+
+                if let Some(journal) = self.journal.replace(None) {
+                    // the flushed keys are journaled, so that a rollback undoes the commit too
+                    let journal = match journal {
+                        Journal::Staged(staged) => Journal::Committed(HashSet::new(), staged),
+                        journal => journal,
+                    };
+                    self.journal.replace(Some(journal));
+
+                    try_self!(self, self.flush(staged).await);
+
+                    return Ok((self, ()));
+                }
+
+                try_self!(self, self.begin_journal().await);
+
+                if let Err(error) = self.flush(staged).await {
+                    try_self!(self, self.rollback_journal().await);
+
+                    return Err((self, error));
+                }
+
+                try_self!(self, self.commit_journal().await);
+
+                Ok((self, ()))
             }
 
-            fn get_data_prefix(&self, table_name: &str) -> String {
-                format!("__gluesql-v0.2__/{}/data/{}", self.namespace, table_name)
+            async fn rollback(self) -> MutResult<Self, ()> {
+                match self.staged.replace(None) {
+                    Some(_) => Ok((self, ())),
+                    None => Err((self, TransactionError::NotStarted.into())),
+                }
             }
         }
 
         #[async_trait(?Send)]
         impl StoreMut<$StorageKey> for $Storage {
             async fn generate_id(self, table_name: &str) -> MutResult<Self, $StorageKey> {
+                try_self!(self, self.init().await);
+
                 let prefix = self.get_id_prefix(table_name);
                 let table_name = table_name.to_string();
 
-                let key = match $get_item(&prefix).as_string() {
+                let key = match try_self!(self, self.get(&prefix).await) {
                     Some(v) => {
                         let $StorageKey { id, .. } = try_into!(self, serde_json::from_str(&v));
 
@@ -97,80 +738,76 @@ macro_rules! generate_storage_code {
                 };
 
                 let serialized = try_into!(self, serde_json::to_string(&key));
-                $set_item(&prefix, &serialized);
+                try_self!(self, self.set(&prefix, &serialized).await);
 
                 Ok((self, key))
             }
 
             async fn insert_schema(self, schema: &Schema) -> MutResult<Self, ()> {
+                try_self!(self, self.init().await);
+
                 let prefix = self.get_schema_prefix(&schema.table_name);
                 let schema = try_into!(self, serde_json::to_string(&schema));
 
-                $set_item(&prefix, &schema);
+                try_self!(self, self.set(&prefix, &schema).await);
 
                 Ok((self, ()))
             }
 
             async fn delete_schema(self, table_name: &str) -> MutResult<Self, ()> {
+                try_self!(self, self.init().await);
+
                 let schema_prefix = self.get_schema_prefix(table_name);
-                let data_prefix = self.get_data_prefix(table_name);
+                let index_prefix = self.get_index_prefix(table_name);
 
-                $remove_item(&schema_prefix);
-                $remove_item(&data_prefix);
+                for id in try_self!(self, self.fetch_ids(table_name).await) {
+                    let data_prefix = self.get_data_prefix(table_name, id);
+
+                    try_self!(self, self.remove(&data_prefix).await);
+                }
+
+                try_self!(self, self.remove(&schema_prefix).await);
+                try_self!(self, self.remove(&index_prefix).await);
 
                 Ok((self, ()))
             }
 
             async fn insert_data(self, key: &$StorageKey, row: Row) -> MutResult<Self, ()> {
-                let prefix = self.get_data_prefix(&key.table_name);
-                let item = (key.id, row);
+                try_self!(self, self.init().await);
 
-                let mut items = match $get_item(&prefix).as_string() {
-                    Some(v) => {
-                        let items: Vec<(u64, Row)> = try_into!(self, serde_json::from_str(&v));
+                let prefix = self.get_data_prefix(&key.table_name, key.id);
+                let exists = try_self!(self, self.get(&prefix).await).is_some();
 
-                        items
-                    }
-                    None => vec![],
-                };
+                let row = try_into!(self, serde_json::to_string(&row));
+                try_self!(self, self.set(&prefix, &row).await);
 
-                let items = match items.iter().position(|(id, _)| id == &key.id) {
-                    Some(index) => {
-                        items[index] = item;
+                if !exists {
+                    let mut ids = try_self!(self, self.fetch_ids(&key.table_name).await);
+                    ids.push(key.id);
 
-                        items
-                    }
-                    None => {
-                        items.push(item);
-
-                        items
-                    }
-                };
-
-                let items = try_into!(self, serde_json::to_string(&items));
-                $set_item(&prefix, &items);
+                    let index_prefix = self.get_index_prefix(&key.table_name);
+                    let ids = try_into!(self, serde_json::to_string(&ids));
+                    try_self!(self, self.set(&index_prefix, &ids).await);
+                }
 
                 Ok((self, ()))
             }
 
             async fn delete_data(self, key: &$StorageKey) -> MutResult<Self, ()> {
-                let prefix = self.get_data_prefix(&key.table_name);
+                try_self!(self, self.init().await);
 
-                let mut items = match $get_item(&prefix).as_string() {
-                    Some(v) => {
-                        let items: Vec<(u64, Row)> = try_into!(self, serde_json::from_str(&v));
+                let prefix = self.get_data_prefix(&key.table_name, key.id);
+                let mut ids = try_self!(self, self.fetch_ids(&key.table_name).await);
 
-                        items
-                    }
-                    None => vec![],
-                };
+                if let Some(index) = ids.iter().position(|id| id == &key.id) {
+                    ids.remove(index);
 
-                if let Some(index) = items.iter().position(|(id, _)| id == &key.id) {
-                    items.remove(index);
+                    let index_prefix = self.get_index_prefix(&key.table_name);
+                    let ids = try_into!(self, serde_json::to_string(&ids));
+                    try_self!(self, self.set(&index_prefix, &ids).await);
                 }
 
-                let items = try_into!(self, serde_json::to_string(&items));
-                $set_item(&prefix, &items);
+                try_self!(self, self.remove(&prefix).await);
 
                 Ok((self, ()))
             }
@@ -179,9 +816,11 @@ macro_rules! generate_storage_code {
         #[async_trait(?Send)]
         impl Store<$StorageKey> for $Storage {
             async fn fetch_schema(&self, table_name: &str) -> Result<Option<Schema>> {
+                self.init().await?;
+
                 let prefix = self.get_schema_prefix(table_name);
 
-                let schema = match $get_item(&prefix).as_string() {
+                let schema = match self.get(&prefix).await? {
                     Some(schema) => Some(try_into!(serde_json::from_str(&schema))),
                     None => None,
                 };
@@ -190,30 +829,23 @@ macro_rules! generate_storage_code {
             }
 
             async fn scan_data(&self, table_name: &str) -> Result<RowIter<$StorageKey>> {
-                let prefix = self.get_data_prefix(table_name);
-
-                let items = match $get_item(&prefix).as_string() {
-                    Some(items) => {
-                        let items: Vec<(u64, Row)> = try_into!(serde_json::from_str(&items));
-
-                        items
-                            .into_iter()
-                            .map(|(id, row)| {
-                                let key = $StorageKey {
-                                    table_name: table_name.to_string(),
-                                    id,
-                                };
-
-                                (key, row)
-                            })
-                            .collect()
-                    }
-                    None => vec![],
-                };
+                self.init().await?;
+
+                let items = self
+                    .fetch_rows(table_name)
+                    .await?
+                    .into_iter()
+                    .map(|(id, row)| {
+                        let key = $StorageKey {
+                            table_name: table_name.to_string(),
+                            id,
+                        };
 
-                let items = items.into_iter().map(Ok);
+                        Ok((key, row))
+                    })
+                    .collect::<Vec<_>>();
 
-                Ok(Box::new(items))
+                Ok(Box::new(items.into_iter()))
             }
         }
 
@@ -224,14 +856,15 @@ macro_rules! generate_storage_code {
                 table_name: &str,
                 new_table_name: &str,
             ) -> MutResult<Self, ()> {
+                try_self!(self, self.init().await);
+
                 // update schema
                 let schema_prefix = self.get_schema_prefix(table_name);
 
                 let schema = try_self!(
                     self,
-                    $get_item(&schema_prefix)
-                        .as_string()
-                        .ok_or(AlterTableError::TableNotFound(table_name.to_string()))
+                    try_self!(self, self.get(&schema_prefix).await)
+                        .ok_or_else(|| AlterTableError::TableNotFound(table_name.to_string()))
                 );
                 let mut schema: Schema = try_into!(self, serde_json::from_str(&schema));
 
@@ -239,15 +872,34 @@ macro_rules! generate_storage_code {
 
                 let new_schema_prefix = self.get_schema_prefix(new_table_name);
                 let schema = try_into!(self, serde_json::to_string(&schema));
-                $set_item(&new_schema_prefix, &schema);
-                $remove_item(&schema_prefix);
+                try_self!(self, self.set(&new_schema_prefix, &schema).await);
+                try_self!(self, self.remove(&schema_prefix).await);
 
                 // migrate data
-                let data_prefix = self.get_data_prefix(table_name);
-                let new_data_prefix = self.get_data_prefix(new_table_name);
+                let items = try_self!(self, self.fetch_rows(table_name).await);
+                try_self!(self, self.insert_rows(new_table_name, &items).await);
 
-                if let Some(data) = $get_item(&data_prefix).as_string() {
-                    $set_item(&new_data_prefix, &data);
+                for (id, _) in items.iter() {
+                    let data_prefix = self.get_data_prefix(table_name, *id);
+
+                    try_self!(self, self.remove(&data_prefix).await);
+                }
+
+                // migrate index and id counter
+                for (prefix, new_prefix) in [
+                    (
+                        self.get_index_prefix(table_name),
+                        self.get_index_prefix(new_table_name),
+                    ),
+                    (
+                        self.get_id_prefix(table_name),
+                        self.get_id_prefix(new_table_name),
+                    ),
+                ] {
+                    if let Some(value) = try_self!(self, self.get(&prefix).await) {
+                        try_self!(self, self.set(&new_prefix, &value).await);
+                        try_self!(self, self.remove(&prefix).await);
+                    }
                 }
 
                 Ok((self, ()))
@@ -259,12 +911,13 @@ macro_rules! generate_storage_code {
                 old_column_name: &str,
                 new_column_name: &str,
             ) -> MutResult<Self, ()> {
+                try_self!(self, self.init().await);
+
                 let prefix = self.get_schema_prefix(table_name);
                 let schema = try_self!(
                     self,
-                    $get_item(&prefix)
-                        .as_string()
-                        .ok_or(AlterTableError::TableNotFound(table_name.to_string()))
+                    try_self!(self, self.get(&prefix).await)
+                        .ok_or_else(|| AlterTableError::TableNotFound(table_name.to_string()))
                 );
                 let mut schema: Schema = try_into!(self, serde_json::from_str(&schema));
 
@@ -278,7 +931,7 @@ macro_rules! generate_storage_code {
                 schema.column_defs[i].name.value = new_column_name.to_string();
 
                 let schema = try_into!(self, serde_json::to_string(&schema));
-                $set_item(&prefix, &schema);
+                try_self!(self, self.set(&prefix, &schema).await);
 
                 Ok((self, ()))
             }
@@ -288,12 +941,13 @@ macro_rules! generate_storage_code {
                 table_name: &str,
                 column_def: &ColumnDef,
             ) -> MutResult<Self, ()> {
+                try_self!(self, self.init().await);
+
                 let schema_prefix = self.get_schema_prefix(table_name);
                 let schema = try_self!(
                     self,
-                    $get_item(&schema_prefix)
-                        .as_string()
-                        .ok_or(AlterTableError::TableNotFound(table_name.to_string()))
+                    try_self!(self, self.get(&schema_prefix).await)
+                        .ok_or_else(|| AlterTableError::TableNotFound(table_name.to_string()))
                 );
                 let mut schema: Schema = try_into!(self, serde_json::from_str(&schema));
 
@@ -325,14 +979,14 @@ macro_rules! generate_storage_code {
                         ColumnOption::Default(expr) => Some(expr),
                         _ => None,
                     })
-                    .map(|expr| Value::from_expr(&data_type, nullable, expr))
+                    .map(|expr| Value::from_expr(data_type, nullable, expr))
                     .next();
 
                 let value = match (default, nullable) {
                     (Some(value), _) => try_self!(self, value),
                     (None, true) => try_self!(
                         self,
-                        Value::from_data_type(&data_type, nullable, &AstValue::Null)
+                        Value::from_data_type(data_type, nullable, &AstValue::Null)
                     ),
                     (None, false) => {
                         return Err((
@@ -343,19 +997,9 @@ macro_rules! generate_storage_code {
                 };
 
                 let schema = try_into!(self, serde_json::to_string(&schema));
-                $set_item(&schema_prefix, &schema);
+                try_self!(self, self.set(&schema_prefix, &schema).await);
 
-                let data_prefix = self.get_data_prefix(table_name);
-                let items = match $get_item(&data_prefix).as_string() {
-                    Some(v) => {
-                        let items: Vec<(u64, Row)> = try_into!(self, serde_json::from_str(&v));
-
-                        items
-                    }
-                    None => vec![],
-                };
-
-                let items: Vec<(u64, Row)> = items
+                let items: Vec<(u64, Row)> = try_self!(self, self.fetch_rows(table_name).await)
                     .into_iter()
                     .map(|(id, mut row)| {
                         row.0.push(value.clone());
@@ -364,8 +1008,7 @@ macro_rules! generate_storage_code {
                     })
                     .collect();
 
-                let items = try_into!(self, serde_json::to_string(&items));
-                $set_item(&data_prefix, &items);
+                try_self!(self, self.insert_rows(table_name, &items).await);
 
                 Ok((self, ()))
             }
@@ -376,12 +1019,13 @@ macro_rules! generate_storage_code {
                 column_name: &str,
                 if_exists: bool,
             ) -> MutResult<Self, ()> {
+                try_self!(self, self.init().await);
+
                 let schema_prefix = self.get_schema_prefix(table_name);
                 let schema = try_self!(
                     self,
-                    $get_item(&schema_prefix)
-                        .as_string()
-                        .ok_or(AlterTableError::TableNotFound(table_name.to_string()))
+                    try_self!(self, self.get(&schema_prefix).await)
+                        .ok_or_else(|| AlterTableError::TableNotFound(table_name.to_string()))
                 );
                 let mut schema: Schema = try_into!(self, serde_json::from_str(&schema));
 
@@ -405,19 +1049,9 @@ macro_rules! generate_storage_code {
 
                 schema.column_defs.remove(index);
                 let schema = try_into!(self, serde_json::to_string(&schema));
-                $set_item(&schema_prefix, &schema);
+                try_self!(self, self.set(&schema_prefix, &schema).await);
 
-                let data_prefix = self.get_data_prefix(table_name);
-                let items = match $get_item(&data_prefix).as_string() {
-                    Some(v) => {
-                        let items: Vec<(u64, Row)> = try_into!(self, serde_json::from_str(&v));
-
-                        items
-                    }
-                    None => vec![],
-                };
-
-                let items: Vec<(u64, Row)> = items
+                let items: Vec<(u64, Row)> = try_self!(self, self.fetch_rows(table_name).await)
                     .into_iter()
                     .map(|(id, mut row)| {
                         row.0.remove(index);
@@ -426,8 +1060,7 @@ macro_rules! generate_storage_code {
                     })
                     .collect();
 
-                let items = try_into!(self, serde_json::to_string(&items));
-                $set_item(&data_prefix, &items);
+                try_self!(self, self.insert_rows(table_name, &items).await);
 
                 Ok((self, ()))
             }
@@ -452,3 +1085,770 @@ generate_storage_code!(
     sset_item,
     sremove_item
 );
+
+/// Name of the database since the IndexedDB storage was added, back with the v0.2 layout.
+/// Keys carry their own layout prefix, so the name is kept for `migrate` to find the legacy keys.
+const INDEXED_DB_NAME: &str = "__gluesql-v0.2__";
+const INDEXED_DB_STORE_NAME: &str = "items";
+
+#[derive(Debug)]
+pub enum WebStorageError {
+    IndexedDbNotSupported,
+    IndexedDbBlocked,
+    OpfsNotSupported,
+    FsNotSupported,
+    MissingCallback(&'static str),
+    NamespaceExists(String),
+    QuotaExceeded(String),
+    Js(String),
+}
+
+impl fmt::Display for WebStorageError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            WebStorageError::IndexedDbNotSupported => write!(f, "indexedDB is not supported"),
+            WebStorageError::IndexedDbBlocked => {
+                write!(f, "indexedDB is blocked by a connection of another tab")
+            }
+            WebStorageError::OpfsNotSupported => {
+                write!(f, "origin private file system is not supported")
+            }
+            WebStorageError::FsNotSupported => {
+                write!(
+                    f,
+                    "fs storage requires process.getBuiltinModule of Node.js 20.16+ or 22.3+"
+                )
+            }
+            WebStorageError::QuotaExceeded(message) => write!(f, "quota exceeded: {}", message),
+            WebStorageError::NamespaceExists(namespace) => {
+                write!(f, "database already exists: {}", namespace)
+            }
+            WebStorageError::MissingCallback(name) => {
+                write!(f, "custom storage has no {} method", name)
+            }
+            WebStorageError::Js(message) => write!(f, "{}", message),
+        }
+    }
+}
+
+impl std::error::Error for WebStorageError {}
+
+impl From<WebStorageError> for Error {
+    fn from(e: WebStorageError) -> Self {
+        Error::Storage(Box::new(e))
+    }
+}
+
+impl From<JsValue> for WebStorageError {
+    fn from(value: JsValue) -> Self {
+        let message = value
+            .as_string()
+            .or_else(|| {
+                Reflect::get(&value, &JsValue::from_str("message"))
+                    .ok()
+                    .and_then(|message| message.as_string())
+            })
+            .unwrap_or_else(|| format!("{:?}", value));
+
+        let quota_exceeded = value
+            .dyn_ref::<DomException>()
+            // older versions of Firefox name the error after its internal code
+            .is_some_and(|error| {
+                matches!(
+                    error.name().as_str(),
+                    "QuotaExceededError" | "NS_ERROR_DOM_QUOTA_REACHED"
+                )
+            });
+
+        match quota_exceeded {
+            true => WebStorageError::QuotaExceeded(message),
+            false => WebStorageError::Js(message),
+        }
+    }
+}
+
+type Callback<T> = Closure<dyn FnMut() -> std::result::Result<T, JsValue>>;
+
+pub(crate) fn js_error(value: JsValue) -> Error {
+    WebStorageError::from(value).into()
+}
+
+async fn wait(request: &IdbRequest) -> Result<JsValue> {
+    let mut handlers = None;
+
+    let promise = Promise::new(&mut |resolve, reject| {
+        let success_request = request.clone();
+        let on_success: Callback<JsValue> = Closure::once(move || {
+            let result = success_request.result().unwrap_or(JsValue::UNDEFINED);
+
+            resolve.call1(&JsValue::NULL, &result)
+        });
+
+        let error_request = request.clone();
+        let on_error = Closure::once(move |event: Event| {
+            // keeps the failed request from aborting its transaction, which would undo the
+            // requests before it
+            event.prevent_default();
+
+            let error = match error_request.error() {
+                Ok(Some(error)) => error.into(),
+                _ => JsValue::from_str("unknown indexedDB request error"),
+            };
+
+            reject.call1(&JsValue::NULL, &error)
+        });
+
+        request.set_onsuccess(Some(on_success.as_ref().unchecked_ref()));
+        request.set_onerror(Some(on_error.as_ref().unchecked_ref()));
+
+        handlers = Some((on_success, on_error));
+    });
+
+    let result = JsFuture::from(promise).await.map_err(js_error);
+
+    request.set_onsuccess(None);
+    request.set_onerror(None);
+    drop(handlers);
+
+    result
+}
+
+/// Handlers of a transaction, which are unset when dropped so that it never calls them
+/// afterwards.
+struct TransactionHandlers {
+    transaction: IdbTransaction,
+    _closures: (Callback<JsValue>, Callback<JsValue>),
+}
+
+impl Drop for TransactionHandlers {
+    fn drop(&mut self) {
+        self.transaction.set_oncomplete(None);
+        self.transaction.set_onabort(None);
+    }
+}
+
+/// Settles once `transaction` commits, or fails with the error which aborted it.
+/// The handlers are set right away, as the transaction may end before the future is polled.
+fn completion(transaction: &IdbTransaction) -> LocalBoxFuture<'static, Result<()>> {
+    let mut handlers = None;
+
+    let promise = Promise::new(&mut |resolve, reject| {
+        let on_complete: Callback<JsValue> = Closure::once(move || resolve.call0(&JsValue::NULL));
+
+        let abort_transaction = transaction.clone();
+        let on_abort: Callback<JsValue> = Closure::once(move || {
+            let error = match abort_transaction.error() {
+                Some(error) => error.into(),
+                None => JsValue::from_str("indexedDB transaction aborted"),
+            };
+
+            reject.call1(&JsValue::NULL, &error)
+        });
+
+        transaction.set_oncomplete(Some(on_complete.as_ref().unchecked_ref()));
+        transaction.set_onabort(Some(on_abort.as_ref().unchecked_ref()));
+
+        handlers = Some(TransactionHandlers {
+            transaction: transaction.clone(),
+            _closures: (on_complete, on_abort),
+        });
+    });
+    let promise = JsFuture::from(promise);
+
+    Box::pin(async move {
+        let result = promise.await.map(|_| ()).map_err(js_error);
+        drop(handlers);
+
+        result
+    })
+}
+
+pub struct IndexedDbStorage {
+    namespace: String,
+    initialized: Cell<bool>,
+    journal: RefCell<Option<Journal>>,
+    staged: RefCell<Option<Staged>>,
+    /// Cleared when another tab asks to upgrade or delete the database, which closes it.
+    database: Rc<RefCell<Option<IdbDatabase>>>,
+    /// Transaction of the last request, which the following requests reuse until it commits.
+    transaction: RefCell<Option<IdbTransaction>>,
+    /// Completions of the transactions created since the last `complete`.
+    completions: RefCell<Vec<LocalBoxFuture<'static, Result<()>>>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct IndexedDbKey {
+    pub table_name: String,
+    pub id: u64,
+}
+
+impl IndexedDbStorage {
+    pub fn new(namespace: String) -> Result<Self> {
+        Ok(Self {
+            namespace,
+            initialized: Cell::new(false),
+            journal: RefCell::new(None),
+            staged: RefCell::new(None),
+            database: Rc::new(RefCell::new(None)),
+            transaction: RefCell::new(None),
+            completions: RefCell::new(vec![]),
+        })
+    }
+
+    async fn open(&self) -> Result<IdbDatabase> {
+        if let Some(database) = self.database.borrow().as_ref() {
+            return Ok(database.clone());
+        }
+
+        let factory = Reflect::get(&js_sys::global(), &JsValue::from_str("indexedDB"))
+            .map_err(js_error)?
+            .dyn_into::<IdbFactory>()
+            .map_err(|_| WebStorageError::IndexedDbNotSupported)?;
+
+        let request = factory
+            .open_with_u32(INDEXED_DB_NAME, 1)
+            .map_err(js_error)?;
+
+        let upgrade_request = request.clone();
+        let on_upgrade_needed: Callback<()> = Closure::once(move || {
+            let database: IdbDatabase = upgrade_request.result()?.unchecked_into();
+
+            database
+                .create_object_store(INDEXED_DB_STORE_NAME)
+                .map(|_| ())
+        });
+        request.set_onupgradeneeded(Some(on_upgrade_needed.as_ref().unchecked_ref()));
+
+        // an older connection of another tab which does not close keeps the request blocked
+        let blocked = Promise::new(&mut |_, reject| {
+            let on_blocked = Closure::once_into_js(move || {
+                let _ = reject.call0(&JsValue::NULL);
+            });
+
+            request.set_onblocked(Some(on_blocked.unchecked_ref()));
+        });
+
+        let opened = Box::pin(wait(&request));
+        let database = match select(opened, JsFuture::from(blocked)).await {
+            Either::Left((database, _)) => database,
+            Either::Right(_) => Err(WebStorageError::IndexedDbBlocked.into()),
+        };
+        request.set_onupgradeneeded(None);
+        request.set_onblocked(None);
+
+        let database: IdbDatabase = database?.unchecked_into();
+
+        // closes the connection instead of blocking another tab, and opens it again on next use
+        let cell = Rc::clone(&self.database);
+        let on_version_change = Closure::once_into_js(move || {
+            if let Some(database) = cell.replace(None) {
+                database.close();
+            }
+        });
+        database.set_onversionchange(Some(on_version_change.unchecked_ref()));
+
+        self.database.replace(Some(database.clone()));
+
+        Ok(database)
+    }
+
+    /// Runs the request `make` creates, in the transaction of the previous request while it is
+    /// still active.
+    /// A transaction commits on its own once a task ends without new requests, so every request
+    /// of a storage operation shares one.
+    async fn request<F>(&self, make: F) -> Result<JsValue>
+    where
+        F: Fn(&IdbObjectStore) -> std::result::Result<IdbRequest, JsValue>,
+    {
+        let make = |transaction: &IdbTransaction| {
+            transaction
+                .object_store(INDEXED_DB_STORE_NAME)
+                .and_then(|store| make(&store))
+        };
+
+        let transaction = self.transaction.borrow().clone();
+        let request = match transaction.map(|transaction| make(&transaction)) {
+            Some(Ok(request)) => request,
+            _ => {
+                let transaction = self
+                    .open()
+                    .await?
+                    .transaction_with_str_and_mode(
+                        INDEXED_DB_STORE_NAME,
+                        IdbTransactionMode::Readwrite,
+                    )
+                    .map_err(js_error)?;
+                self.transaction.replace(Some(transaction.clone()));
+                self.completions.borrow_mut().push(completion(&transaction));
+
+                make(&transaction).map_err(js_error)?
+            }
+        };
+
+        wait(&request).await
+    }
+
+    /// Waits for the transactions of the storage operation which just ended to commit.
+    /// A write is only durable once its transaction commits, which may still abort, e.g. when the
+    /// quota is exceeded.
+    pub async fn complete(&self) -> Result<()> {
+        self.transaction.replace(None);
+
+        let completions = self.completions.replace(vec![]);
+        let mut result = Ok(());
+
+        for completion in completions {
+            let completed = completion.await;
+
+            result = result.and(completed);
+        }
+
+        result
+    }
+
+    async fn get_item(&self, key: &str) -> Result<Option<String>> {
+        self.request(|store| store.get(&JsValue::from_str(key)))
+            .await
+            .map(|value| value.as_string())
+    }
+
+    async fn set_item(&self, key: &str, value: &str) -> Result<()> {
+        self.request(|store| store.put_with_key(&JsValue::from_str(value), &JsValue::from_str(key)))
+            .await
+            .map(|_| ())
+    }
+
+    async fn remove_item(&self, key: &str) -> Result<()> {
+        self.request(|store| store.delete(&JsValue::from_str(key)))
+            .await
+            .map(|_| ())
+    }
+
+    async fn scan_keys(&self, prefix: &str) -> Result<Vec<String>> {
+        let range = IdbKeyRange::bound(
+            &JsValue::from_str(prefix),
+            &JsValue::from_str(&format!("{}\u{ffff}", prefix)),
+        )
+        .map_err(js_error)?;
+        let keys = self
+            .request(|store| store.get_all_keys_with_key(&range))
+            .await?
+            .unchecked_into::<Array>()
+            .iter()
+            .filter_map(|key| key.as_string())
+            .collect();
+
+        Ok(keys)
+    }
+}
+
+impl_storage!(IndexedDbStorage, IndexedDbKey);
+
+const OPFS_DIRECTORY_NAME: &str = "__gluesql-v0.3__";
+
+/// Storage in the Origin Private File System, which keeps every key as a file named after the
+/// URI encoded key, under a single directory shared by all namespaces.
+/// Every write replaces a whole file through `createWritable`, as sync access handles are only
+/// available in workers.
+pub struct OpfsStorage {
+    namespace: String,
+    initialized: Cell<bool>,
+    journal: RefCell<Option<Journal>>,
+    staged: RefCell<Option<Staged>>,
+    directory: RefCell<Option<FileSystemDirectoryHandle>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct OpfsKey {
+    pub table_name: String,
+    pub id: u64,
+}
+
+fn is_not_found(error: &JsValue) -> bool {
+    error
+        .dyn_ref::<DomException>()
+        .is_some_and(|error| error.name() == "NotFoundError")
+}
+
+impl OpfsStorage {
+    pub fn new(namespace: String) -> Result<Self> {
+        Ok(Self {
+            namespace,
+            initialized: Cell::new(false),
+            journal: RefCell::new(None),
+            staged: RefCell::new(None),
+            directory: RefCell::new(None),
+        })
+    }
+
+    async fn open(&self) -> Result<FileSystemDirectoryHandle> {
+        if let Some(directory) = self.directory.borrow().as_ref() {
+            return Ok(directory.clone());
+        }
+
+        // navigator.storage is available both in windows and in workers
+        let storage = Reflect::get(&js_sys::global(), &JsValue::from_str("navigator"))
+            .and_then(|navigator| Reflect::get(&navigator, &JsValue::from_str("storage")))
+            .map_err(js_error)?;
+        let has_directory = !storage.is_undefined()
+            && Reflect::has(&storage, &JsValue::from_str("getDirectory")).map_err(js_error)?;
+
+        if !has_directory {
+            return Err(WebStorageError::OpfsNotSupported.into());
+        }
+
+        let root: FileSystemDirectoryHandle =
+            JsFuture::from(storage.unchecked_into::<StorageManager>().get_directory())
+                .await
+                .map_err(js_error)?
+                .unchecked_into();
+
+        let options = FileSystemGetDirectoryOptions::new();
+        options.set_create(true);
+        let directory: FileSystemDirectoryHandle =
+            JsFuture::from(root.get_directory_handle_with_options(OPFS_DIRECTORY_NAME, &options))
+                .await
+                .map_err(js_error)?
+                .unchecked_into();
+
+        self.directory.replace(Some(directory.clone()));
+
+        Ok(directory)
+    }
+
+    fn file_name(key: &str) -> String {
+        js_sys::encode_uri_component(key).into()
+    }
+
+    async fn get_item(&self, key: &str) -> Result<Option<String>> {
+        let directory = self.open().await?;
+        let handle = match JsFuture::from(directory.get_file_handle(&Self::file_name(key))).await {
+            Ok(handle) => handle.unchecked_into::<FileSystemFileHandle>(),
+            Err(error) if is_not_found(&error) => return Ok(None),
+            Err(error) => return Err(js_error(error)),
+        };
+
+        let file: Blob = JsFuture::from(handle.get_file())
+            .await
+            .map_err(js_error)?
+            .unchecked_into();
+        let text = JsFuture::from(file.text()).await.map_err(js_error)?;
+
+        Ok(text.as_string())
+    }
+
+    async fn set_item(&self, key: &str, value: &str) -> Result<()> {
+        let directory = self.open().await?;
+
+        let options = FileSystemGetFileOptions::new();
+        options.set_create(true);
+        let handle: FileSystemFileHandle =
+            JsFuture::from(directory.get_file_handle_with_options(&Self::file_name(key), &options))
+                .await
+                .map_err(js_error)?
+                .unchecked_into();
+
+        // the file is replaced only when the stream is closed
+        let stream: FileSystemWritableFileStream = JsFuture::from(handle.create_writable())
+            .await
+            .map_err(js_error)?
+            .unchecked_into();
+        JsFuture::from(stream.write_with_str(value).map_err(js_error)?)
+            .await
+            .map_err(js_error)?;
+        JsFuture::from(stream.close()).await.map_err(js_error)?;
+
+        Ok(())
+    }
+
+    async fn remove_item(&self, key: &str) -> Result<()> {
+        let directory = self.open().await?;
+
+        match JsFuture::from(directory.remove_entry(&Self::file_name(key))).await {
+            Err(error) if !is_not_found(&error) => Err(js_error(error)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Writes are done once their file stream is closed.
+    pub async fn complete(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn scan_keys(&self, prefix: &str) -> Result<Vec<String>> {
+        let names = self.open().await?.keys();
+        let mut keys = vec![];
+
+        loop {
+            let next = JsFuture::from(names.next().map_err(js_error)?)
+                .await
+                .map_err(js_error)?;
+            let done = Reflect::get(&next, &JsValue::from_str("done")).map_err(js_error)?;
+
+            if done.is_truthy() {
+                break;
+            }
+
+            let name = Reflect::get(&next, &JsValue::from_str("value"))
+                .map_err(js_error)?
+                .as_string()
+                .unwrap_or_default();
+            let key: String = js_sys::decode_uri_component(&name)
+                .map_err(js_error)?
+                .into();
+
+            if key.starts_with(prefix) {
+                keys.push(key);
+            }
+        }
+
+        Ok(keys)
+    }
+}
+
+impl_storage!(OpfsStorage, OpfsKey);
+
+/// Storage for Node.js, which keeps every key as a file named after the URI encoded key, under
+/// the directory given as namespace.
+/// Files hold the same values as the keys of the web storages.
+pub struct FsStorage {
+    namespace: String,
+    initialized: Cell<bool>,
+    journal: RefCell<Option<Journal>>,
+    staged: RefCell<Option<Staged>>,
+    fs: RefCell<Option<JsValue>>,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct FsKey {
+    pub table_name: String,
+    pub id: u64,
+}
+
+fn is_enoent(error: &JsValue) -> bool {
+    Reflect::get(error, &JsValue::from_str("code"))
+        .ok()
+        .and_then(|code| code.as_string())
+        .is_some_and(|code| code == "ENOENT")
+}
+
+impl FsStorage {
+    pub fn new(namespace: String) -> Result<Self> {
+        Ok(Self {
+            namespace,
+            initialized: Cell::new(false),
+            journal: RefCell::new(None),
+            staged: RefCell::new(None),
+            fs: RefCell::new(None),
+        })
+    }
+
+    /// `fs.promises`, loaded at runtime so that browser bundles do not import `fs`.
+    async fn open(&self) -> Result<JsValue> {
+        if let Some(fs) = self.fs.borrow().as_ref() {
+            return Ok(fs.clone());
+        }
+
+        let get_builtin_module = Reflect::get(&js_sys::global(), &JsValue::from_str("process"))
+            .and_then(|process| {
+                if process.is_undefined() {
+                    return Ok(JsValue::UNDEFINED);
+                }
+
+                Reflect::get(&process, &JsValue::from_str("getBuiltinModule"))
+            })
+            .map_err(js_error)?
+            .dyn_into::<Function>()
+            .map_err(|_| WebStorageError::FsNotSupported)?;
+
+        let fs = get_builtin_module
+            .call1(&JsValue::NULL, &JsValue::from_str("fs"))
+            .and_then(|fs| Reflect::get(&fs, &JsValue::from_str("promises")))
+            .map_err(js_error)?;
+
+        let options = Object::new();
+        Reflect::set(&options, &JsValue::from_str("recursive"), &JsValue::TRUE)
+            .map_err(js_error)?;
+        Self::call(
+            &fs,
+            "mkdir",
+            Array::of2(&JsValue::from_str(&self.namespace), &options),
+        )
+        .await?;
+
+        self.fs.replace(Some(fs.clone()));
+
+        Ok(fs)
+    }
+
+    async fn call(fs: &JsValue, name: &str, args: Array) -> Result<JsValue> {
+        Self::try_call(fs, name, args).await.map_err(js_error)
+    }
+
+    async fn try_call(
+        fs: &JsValue,
+        name: &str,
+        args: Array,
+    ) -> std::result::Result<JsValue, JsValue> {
+        let method: Function = Reflect::get(fs, &JsValue::from_str(name))?.unchecked_into();
+        let promise: Promise = method.apply(fs, &args)?.unchecked_into();
+
+        JsFuture::from(promise).await
+    }
+
+    fn path(&self, key: &str) -> JsValue {
+        let file_name = String::from(js_sys::encode_uri_component(key));
+
+        JsValue::from_str(&format!("{}/{}", self.namespace, file_name))
+    }
+
+    async fn get_item(&self, key: &str) -> Result<Option<String>> {
+        let fs = self.open().await?;
+        let args = Array::of2(&self.path(key), &JsValue::from_str("utf8"));
+
+        match Self::try_call(&fs, "readFile", args).await {
+            Ok(value) => Ok(value.as_string()),
+            Err(error) if is_enoent(&error) => Ok(None),
+            Err(error) => Err(js_error(error)),
+        }
+    }
+
+    async fn set_item(&self, key: &str, value: &str) -> Result<()> {
+        let fs = self.open().await?;
+        let path = self.path(key);
+        let temp_path = JsValue::from_str(&format!("{}.tmp", path.as_string().unwrap()));
+
+        // writes a temporary file first, so a crash never leaves a half written value
+        let args = Array::of2(&temp_path, &JsValue::from_str(value));
+        Self::call(&fs, "writeFile", args).await?;
+        Self::call(&fs, "rename", Array::of2(&temp_path, &path)).await?;
+
+        Ok(())
+    }
+
+    async fn remove_item(&self, key: &str) -> Result<()> {
+        let fs = self.open().await?;
+
+        match Self::try_call(&fs, "unlink", Array::of1(&self.path(key))).await {
+            Err(error) if !is_enoent(&error) => Err(js_error(error)),
+            _ => Ok(()),
+        }
+    }
+
+    /// Writes are done once their promise resolves.
+    pub async fn complete(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn scan_keys(&self, prefix: &str) -> Result<Vec<String>> {
+        let fs = self.open().await?;
+        let args = Array::of1(&JsValue::from_str(&self.namespace));
+        let mut keys = vec![];
+
+        for name in Array::from(&Self::call(&fs, "readdir", args).await?).iter() {
+            let name = name.as_string().unwrap_or_default();
+
+            if name.ends_with(".tmp") {
+                continue;
+            }
+
+            let key: String = js_sys::decode_uri_component(&name)
+                .map_err(js_error)?
+                .into();
+
+            if key.starts_with(prefix) {
+                keys.push(key);
+            }
+        }
+
+        Ok(keys)
+    }
+}
+
+impl_storage!(FsStorage, FsKey);
+
+const CALLBACKS: [&str; 4] = ["get", "set", "remove", "keys"];
+
+/// Storage backed by a JavaScript object with async `get`, `set`, `remove` and `keys` methods,
+/// which uses the same key layout as `localStorage`.
+/// `keys(prefix)` may return every key, only the ones starting with `prefix` are used.
+pub struct JsCallbackStorage {
+    namespace: String,
+    initialized: Cell<bool>,
+    journal: RefCell<Option<Journal>>,
+    staged: RefCell<Option<Staged>>,
+    callbacks: JsValue,
+}
+
+#[derive(Clone, Debug, Serialize, Deserialize)]
+pub struct JsCallbackKey {
+    pub table_name: String,
+    pub id: u64,
+}
+
+impl JsCallbackStorage {
+    pub fn new(callbacks: JsValue, namespace: String) -> Result<Self> {
+        for name in CALLBACKS.iter() {
+            let callback = Reflect::get(&callbacks, &JsValue::from_str(name)).map_err(js_error)?;
+
+            if !callback.is_function() {
+                return Err(WebStorageError::MissingCallback(name).into());
+            }
+        }
+
+        Ok(Self {
+            namespace,
+            initialized: Cell::new(false),
+            journal: RefCell::new(None),
+            staged: RefCell::new(None),
+            callbacks,
+        })
+    }
+
+    async fn call(&self, name: &str, args: Array) -> Result<JsValue> {
+        let callback: Function = Reflect::get(&self.callbacks, &JsValue::from_str(name))
+            .map_err(js_error)?
+            .unchecked_into();
+        let value = callback.apply(&self.callbacks, &args).map_err(js_error)?;
+
+        JsFuture::from(Promise::resolve(&value))
+            .await
+            .map_err(js_error)
+    }
+
+    async fn get_item(&self, key: &str) -> Result<Option<String>> {
+        let args = Array::of1(&JsValue::from_str(key));
+
+        self.call("get", args).await.map(|value| value.as_string())
+    }
+
+    async fn set_item(&self, key: &str, value: &str) -> Result<()> {
+        let args = Array::of2(&JsValue::from_str(key), &JsValue::from_str(value));
+
+        self.call("set", args).await.map(|_| ())
+    }
+
+    async fn remove_item(&self, key: &str) -> Result<()> {
+        let args = Array::of1(&JsValue::from_str(key));
+
+        self.call("remove", args).await.map(|_| ())
+    }
+
+    /// Writes are done once the callbacks resolve.
+    pub async fn complete(&self) -> Result<()> {
+        Ok(())
+    }
+
+    async fn scan_keys(&self, prefix: &str) -> Result<Vec<String>> {
+        let args = Array::of1(&JsValue::from_str(prefix));
+        let keys = Array::from(&self.call("keys", args).await?)
+            .iter()
+            .filter_map(|key| key.as_string())
+            .filter(|key| key.starts_with(prefix))
+            .collect();
+
+        Ok(keys)
+    }
+}
+
+impl_storage!(JsCallbackStorage, JsCallbackKey);
@@ -0,0 +1,158 @@
+use gluesql::Glue;
+use js_sys::{Reflect, JSON};
+use std::collections::{BTreeMap, BTreeSet};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::Storage;
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+async fn select(glue: &mut Glue, sql: &str) -> String {
+    let result = JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+    let data = Reflect::get(&result, &0.into())
+        .and_then(|payload| Reflect::get(&payload, &JsValue::from_str("data")))
+        .unwrap();
+
+    JSON::stringify(&data).unwrap().as_string().unwrap()
+}
+
+async fn test(mut glue: Glue) {
+    glue.set_atomic(true);
+
+    let sql = "
+        DROP TABLE IF EXISTS Item;
+        DROP TABLE IF EXISTS Other;
+        CREATE TABLE Item (id INTEGER);
+        INSERT INTO Item VALUES (1);
+    ";
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    let sql = "
+        INSERT INTO Item VALUES (2);
+        UPDATE Item SET id = 3 WHERE id = 1;
+        CREATE TABLE Other (id INTEGER);
+        INSERT INTO Missing VALUES (4);
+    ";
+    assert!(JsFuture::from(glue.execute(sql.to_owned())).await.is_err());
+
+    assert_eq!(select(&mut glue, "SELECT id FROM Item;").await, "[[1]]");
+    assert!(
+        JsFuture::from(glue.execute("SELECT * FROM Other;".to_owned()))
+            .await
+            .is_err()
+    );
+}
+
+#[wasm_bindgen_test]
+async fn memory_atomic() {
+    test(Glue::new("memory", &JsValue::UNDEFINED).unwrap()).await;
+}
+
+#[wasm_bindgen_test]
+async fn session_atomic() {
+    test(Glue::new("sessionstorage", &JsValue::from_str("atomic")).unwrap()).await;
+}
+
+fn session_storage() -> Storage {
+    Reflect::get(&js_sys::global(), &JsValue::from_str("sessionStorage"))
+        .unwrap()
+        .unchecked_into()
+}
+
+fn namespace_items(prefix: &str) -> BTreeMap<String, String> {
+    let storage = session_storage();
+
+    (0..storage.length().unwrap())
+        .filter_map(|index| storage.key(index).unwrap())
+        .filter(|key| key.starts_with(prefix))
+        .map(|key| {
+            let value = storage.get_item(&key).unwrap().unwrap();
+
+            (key, value)
+        })
+        .collect()
+}
+
+#[wasm_bindgen_test]
+async fn atomic_restore_journal() {
+    let namespace = JsValue::from_str("atomic-journal");
+    let prefix = "__gluesql-v0.3__/atomic-journal/";
+    let mut glue = Glue::new("sessionstorage", &namespace).unwrap();
+
+    let sql = "
+        DROP TABLE IF EXISTS Item;
+        CREATE TABLE Item (id INTEGER);
+        INSERT INTO Item VALUES (1);
+    ";
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    let before = namespace_items(prefix);
+    let sql = "
+        INSERT INTO Item VALUES (2);
+        DELETE FROM Item WHERE id = 1;
+    ";
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+    let after = namespace_items(prefix);
+
+    // the journal an atomic call leaves behind when the tab closes before it ends
+    let storage = session_storage();
+    let keys = before.keys().chain(after.keys()).collect::<BTreeSet<_>>();
+    let changed = keys
+        .into_iter()
+        .filter(|key| before.get(*key) != after.get(*key))
+        .enumerate();
+
+    for (index, key) in changed {
+        let entry = JSON::stringify(&js_sys::Array::of2(
+            &JsValue::from_str(key),
+            &before
+                .get(key)
+                .map_or(JsValue::NULL, |value| value.as_str().into()),
+        ))
+        .unwrap();
+        let journal_key = format!("{}journal/{}", prefix, index + 1);
+
+        storage
+            .set_item(&journal_key, &entry.as_string().unwrap())
+            .unwrap();
+    }
+
+    let mut glue = Glue::new("sessionstorage", &namespace).unwrap();
+    assert_eq!(select(&mut glue, "SELECT id FROM Item;").await, "[[1]]");
+    assert_eq!(namespace_items(prefix), before);
+}
+
+#[wasm_bindgen_test]
+async fn atomic_commit_rollback() {
+    let namespace = JsValue::from_str("atomic-commit");
+    let mut glue = Glue::new("localstorage", &namespace).unwrap();
+    glue.set_atomic(true);
+
+    let sql = "
+        DROP TABLE IF EXISTS Item;
+        CREATE TABLE Item (id INTEGER);
+        INSERT INTO Item VALUES (1);
+    ";
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    JsFuture::from(glue.begin()).await.unwrap();
+    JsFuture::from(glue.execute("INSERT INTO Item VALUES (2);".to_owned()))
+        .await
+        .unwrap();
+
+    // the failing statement rolls the commit back, the transaction stays open
+    let sql = "
+        COMMIT;
+        INSERT INTO Missing VALUES (3);
+    ";
+    assert!(JsFuture::from(glue.execute(sql.to_owned())).await.is_err());
+
+    assert_eq!(select(&mut glue, "SELECT id FROM Item;").await, "[[1],[2]]");
+
+    // without the journal of the commit, the flushed row would stay after the rollback
+    JsFuture::from(glue.rollback()).await.unwrap();
+    assert_eq!(select(&mut glue, "SELECT id FROM Item;").await, "[[1]]");
+}
@@ -0,0 +1,76 @@
+use gluesql::Glue;
+use js_sys::{Object, Reflect, JSON};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn stringify(value: &JsValue) -> String {
+    JSON::stringify(value).unwrap().as_string().unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn export_import() {
+    let mut memory = Glue::new("memory", &JsValue::UNDEFINED).unwrap();
+
+    let sql = "
+        CREATE TABLE Item (id INTEGER, name TEXT);
+        INSERT INTO Item VALUES (1, \"Glue\");
+        INSERT INTO Item VALUES (2, \"Rust\");
+    ";
+    JsFuture::from(memory.execute(sql.to_owned()))
+        .await
+        .unwrap();
+
+    let backup = JsFuture::from(memory.export_json()).await.unwrap();
+    assert_eq!(Reflect::get(&backup, &"version".into()).unwrap(), 1);
+
+    let mut session = Glue::new("sessionstorage", &"backup".into()).unwrap();
+    JsFuture::from(session.execute("DROP TABLE IF EXISTS Item;".to_owned()))
+        .await
+        .unwrap();
+    JsFuture::from(session.import_json(backup.clone()))
+        .await
+        .unwrap();
+
+    let sql = "
+        INSERT INTO Item VALUES (3, \"SQL\");
+        SELECT * FROM Item;
+    ";
+    let result = JsFuture::from(session.execute(sql.to_owned()))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        stringify(&result),
+        r#"[{"query":"INSERT","data":1},{"query":"SELECT","labels":["id","name"],"types":["INT","TEXT"],"data":[[1,"Glue"],[2,"Rust"],[3,"SQL"]]}]"#
+    );
+
+    // importing replaces the table, and accepts the document as a string
+    let backup = JSON::stringify(&backup).unwrap();
+    JsFuture::from(session.import_json(backup.into()))
+        .await
+        .unwrap();
+
+    let result = JsFuture::from(session.execute("SELECT * FROM Item;".to_owned()))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        stringify(&result),
+        r#"[{"query":"SELECT","labels":["id","name"],"types":["INT","TEXT"],"data":[[1,"Glue"],[2,"Rust"]]}]"#
+    );
+
+    let result = JsFuture::from(session.import_json(r#"{"version":0,"tables":[]}"#.into())).await;
+    assert!(result.is_err());
+
+    // JSON.stringify throws on a cyclic object
+    let cyclic = Object::new();
+    Reflect::set(&cyclic, &"self".into(), &cyclic).unwrap();
+    let error = JsFuture::from(session.import_json(cyclic.into()))
+        .await
+        .unwrap_err();
+    assert_eq!(Reflect::get(&error, &"kind".into()).unwrap(), "storage");
+}
@@ -0,0 +1,78 @@
+use gluesql::Glue;
+use js_sys::{Function, Promise, Reflect, Symbol, JSON};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn stringify(value: &JsValue) -> String {
+    JSON::stringify(value).unwrap().as_string().unwrap()
+}
+
+async fn next(cursor: &JsValue) -> JsValue {
+    let next: Function = Reflect::get(cursor, &"next".into())
+        .unwrap()
+        .unchecked_into();
+    let promise: Promise = next.call0(cursor).unwrap().unchecked_into();
+
+    JsFuture::from(promise).await.unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn query_cursor() {
+    let mut glue = Glue::new("memory", &JsValue::UNDEFINED).unwrap();
+
+    let sql = "
+        CREATE TABLE Item (id INTEGER, name TEXT);
+        INSERT INTO Item VALUES (1, \"a\");
+        INSERT INTO Item VALUES (2, \"b\");
+        INSERT INTO Item VALUES (3, \"c\");
+    ";
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    let sql = "SELECT * FROM Item;";
+    let cursor = JsFuture::from(glue.query_cursor(sql.to_owned(), Some(2)))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        stringify(&Reflect::get(&cursor, &"labels".into()).unwrap()),
+        r#"["id","name"]"#
+    );
+    assert_eq!(
+        stringify(&next(&cursor).await),
+        r#"{"done":false,"value":[[1,"a"],[2,"b"]]}"#
+    );
+    assert_eq!(
+        stringify(&next(&cursor).await),
+        r#"{"done":false,"value":[[3,"c"]]}"#
+    );
+    assert_eq!(stringify(&next(&cursor).await), r#"{"done":true}"#);
+
+    // `for await` reads the rows through the cursor its async iterator returns
+    let cursor = JsFuture::from(glue.query_cursor(sql.to_owned(), Some(2)))
+        .await
+        .unwrap();
+    assert_eq!(
+        stringify(&next(&cursor).await),
+        r#"{"done":false,"value":[[1,"a"],[2,"b"]]}"#
+    );
+
+    let iterator: Function = Reflect::get(&cursor, &Symbol::async_iterator())
+        .unwrap()
+        .unchecked_into();
+    let iterator = iterator.call0(&cursor).unwrap();
+    assert_eq!(
+        stringify(&next(&iterator).await),
+        r#"{"done":false,"value":[[3,"c"]]}"#
+    );
+    assert_eq!(stringify(&next(&iterator).await), r#"{"done":true}"#);
+    assert_eq!(stringify(&next(&cursor).await), r#"{"done":true}"#);
+
+    let sql = "DELETE FROM Item;";
+    let result = JsFuture::from(glue.query_cursor(sql.to_owned(), None)).await;
+    assert!(result.is_err());
+}
@@ -0,0 +1,53 @@
+use gluesql::Glue;
+use js_sys::{Function, JSON};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn map_storage(items: &JsValue) -> JsValue {
+    let body = "
+        return {
+            get: async (key) => items.has(key) ? items.get(key) : null,
+            set: async (key, value) => { items.set(key, value); },
+            remove: async (key) => { items.delete(key); },
+            keys: async () => [...items.keys()],
+        };
+    ";
+
+    Function::new_with_args("items", body)
+        .call1(&JsValue::NULL, items)
+        .unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn custom_storage() {
+    let items = js_sys::Map::new();
+    let mut glue = Glue::with_custom_storage(map_storage(&items), None).unwrap();
+
+    let sql = "
+        CREATE TABLE Item (id INTEGER, name TEXT);
+        INSERT INTO Item VALUES (1, \"Glue\"), (2, \"Rust\");
+        DELETE FROM Item WHERE id = 2;
+    ";
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    assert!(items
+        .keys()
+        .into_iter()
+        .any(|key| key.unwrap().as_string().unwrap() == "__gluesql-v0.3__/custom/schema/Item"));
+
+    let mut reopened = Glue::with_custom_storage(map_storage(&items), None).unwrap();
+    let result = JsFuture::from(reopened.execute("SELECT * FROM Item;".to_owned()))
+        .await
+        .unwrap();
+    assert_eq!(
+        JSON::stringify(&result).unwrap().as_string().unwrap(),
+        r#"[{"query":"SELECT","labels":["id","name"],"types":["INT","TEXT"],"data":[[1,"Glue"]]}]"#
+    );
+
+    let incomplete = js_sys::Object::new();
+    assert!(Glue::with_custom_storage(incomplete.into(), None).is_err());
+}
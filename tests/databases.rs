@@ -0,0 +1,132 @@
+use gluesql::Glue;
+use js_sys::{Array, Reflect, JSON};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn session_storage_keys() -> Vec<String> {
+    let storage = Reflect::get(&js_sys::global(), &JsValue::from_str("sessionStorage")).unwrap();
+
+    js_sys::Object::keys(storage.unchecked_ref::<js_sys::Object>())
+        .iter()
+        .filter_map(|key| key.as_string())
+        .collect()
+}
+
+async fn list_databases() -> Vec<String> {
+    let namespaces = JsFuture::from(Glue::list_databases("sessionstorage".to_owned()))
+        .await
+        .unwrap();
+
+    Array::from(&namespaces)
+        .iter()
+        .filter_map(|namespace| namespace.as_string())
+        .collect()
+}
+
+#[wasm_bindgen_test]
+async fn databases() {
+    let storage_type = || "sessionstorage".to_owned();
+
+    for namespace in ["databases-source", "databases-target"].iter() {
+        JsFuture::from(Glue::drop_database(storage_type(), namespace.to_string()))
+            .await
+            .unwrap();
+    }
+
+    let mut glue = Glue::new("sessionstorage", &JsValue::from_str("databases-source")).unwrap();
+    let sql = "
+        CREATE TABLE Item (id INTEGER, name TEXT);
+        INSERT INTO Item VALUES (1, \"Glue\");
+        CREATE TABLE data (id INTEGER);
+        INSERT INTO data VALUES (2);
+        CREATE TABLE index (id INTEGER);
+        INSERT INTO index VALUES (3);
+    ";
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    let namespaces = list_databases().await;
+    assert!(namespaces.contains(&"databases-source".to_owned()));
+    assert!(!namespaces.contains(&"databases-target".to_owned()));
+
+    JsFuture::from(Glue::copy_database(
+        storage_type(),
+        "databases-source".to_owned(),
+        "databases-target".to_owned(),
+    ))
+    .await
+    .unwrap();
+
+    let copied = JsFuture::from(Glue::copy_database(
+        storage_type(),
+        "databases-source".to_owned(),
+        "databases-target".to_owned(),
+    ))
+    .await;
+    assert!(copied.is_err());
+
+    JsFuture::from(Glue::drop_database(
+        storage_type(),
+        "databases-source".to_owned(),
+    ))
+    .await
+    .unwrap();
+
+    // tables named after the kinds of keys must not leave rows behind
+    let source_keys = session_storage_keys()
+        .into_iter()
+        .filter(|key| key.starts_with("__gluesql-v0.3__/databases-source/"))
+        .count();
+    assert_eq!(source_keys, 0);
+
+    let namespaces = list_databases().await;
+    assert!(!namespaces.contains(&"databases-source".to_owned()));
+    assert!(namespaces.contains(&"databases-target".to_owned()));
+
+    let mut target = Glue::new("sessionstorage", &JsValue::from_str("databases-target")).unwrap();
+    let result = JsFuture::from(target.execute("SELECT * FROM Item;".to_owned()))
+        .await
+        .unwrap();
+    assert_eq!(
+        JSON::stringify(&result).unwrap().as_string().unwrap(),
+        r#"[{"query":"SELECT","labels":["id","name"],"types":["INT","TEXT"],"data":[[1,"Glue"]]}]"#
+    );
+
+    let sql = "SELECT * FROM data; SELECT * FROM index;";
+    let result = JsFuture::from(target.execute(sql.to_owned()))
+        .await
+        .unwrap();
+    assert_eq!(
+        JSON::stringify(&result).unwrap().as_string().unwrap(),
+        concat!(
+            r#"[{"query":"SELECT","labels":["id"],"types":["INT"],"data":[[2]]},"#,
+            r#"{"query":"SELECT","labels":["id"],"types":["INT"],"data":[[3]]}]"#,
+        )
+    );
+
+    let memory = JsFuture::from(Glue::list_databases("memory".to_owned())).await;
+    assert!(memory.is_err());
+}
+
+#[wasm_bindgen_test]
+async fn copy_database_to_itself() {
+    let mut glue = Glue::new("localstorage", &JsValue::from_str("databases-self")).unwrap();
+    let sql = "
+        DROP TABLE IF EXISTS Item;
+        CREATE TABLE Item (id INTEGER);
+    ";
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    // rejects instead of waiting forever for the lock it already holds
+    let copied = JsFuture::from(Glue::copy_database(
+        "localstorage".to_owned(),
+        "databases-self".to_owned(),
+        "databases-self".to_owned(),
+    ))
+    .await;
+    assert!(copied.is_err());
+}
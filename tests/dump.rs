@@ -0,0 +1,73 @@
+use gluesql::Glue;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+async fn dump() {
+    let mut glue = Glue::new("memory", &JsValue::UNDEFINED).unwrap();
+
+    let sql = "
+        CREATE TABLE Item (id INTEGER, name TEXT NULL, price FLOAT DEFAULT 1.5);
+        INSERT INTO Item VALUES (1, \"Glue\", 2.5);
+        INSERT INTO Item VALUES (2, NULL, 1);
+        CREATE TABLE Flag (active BOOLEAN);
+        INSERT INTO Flag VALUES (true);
+    ";
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    let dumped = JsFuture::from(glue.dump()).await.unwrap();
+    let dumped = dumped.as_string().unwrap();
+
+    assert_eq!(
+        dumped,
+        "CREATE TABLE \"Flag\" (active BOOLEAN);
+INSERT INTO \"Flag\" VALUES (TRUE);
+CREATE TABLE \"Item\" (id INT, name TEXT NULL, price FLOAT DEFAULT 1.5);
+INSERT INTO \"Item\" VALUES (1, \"Glue\", 2.5);
+INSERT INTO \"Item\" VALUES (2, NULL, 1);
+"
+    );
+
+    let mut restored = Glue::new("memory", &JsValue::UNDEFINED).unwrap();
+    JsFuture::from(restored.execute(dumped.clone()))
+        .await
+        .unwrap();
+
+    let redumped = JsFuture::from(restored.dump()).await.unwrap();
+    assert_eq!(redumped.as_string().unwrap(), dumped);
+}
+
+#[wasm_bindgen_test]
+async fn dump_quotes() {
+    let mut glue = Glue::new("memory", &JsValue::UNDEFINED).unwrap();
+
+    let sql = r#"
+        CREATE TABLE "Order" (id INTEGER, note TEXT);
+        INSERT INTO "Order" VALUES (1, "say ""hi""");
+        INSERT INTO "Order" VALUES (2, """");
+    "#;
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    let dumped = JsFuture::from(glue.dump()).await.unwrap();
+    let dumped = dumped.as_string().unwrap();
+
+    assert_eq!(
+        dumped,
+        r#"CREATE TABLE "Order" (id INT, note TEXT);
+INSERT INTO "Order" VALUES (1, "say ""hi""");
+INSERT INTO "Order" VALUES (2, """");
+"#
+    );
+
+    let mut restored = Glue::new("memory", &JsValue::UNDEFINED).unwrap();
+    JsFuture::from(restored.execute(dumped.clone()))
+        .await
+        .unwrap();
+
+    let redumped = JsFuture::from(restored.dump()).await.unwrap();
+    assert_eq!(redumped.as_string().unwrap(), dumped);
+}
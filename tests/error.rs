@@ -0,0 +1,80 @@
+use gluesql::Glue;
+use js_sys::{Reflect, JSON};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn get(error: &JsValue, key: &str) -> JsValue {
+    Reflect::get(error, &key.into()).unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn parse_error() {
+    let mut glue = Glue::new("memory", &JsValue::UNDEFINED).unwrap();
+
+    let sql = "CREATE TABLE Test (id INTEGER);\nSELEC * FROM Test;";
+    let error = JsFuture::from(glue.execute(sql.to_owned()))
+        .await
+        .unwrap_err();
+
+    assert!(error.is_instance_of::<js_sys::Error>());
+    assert_eq!(get(&error, "name"), "GlueError");
+    assert_eq!(get(&error, "kind"), "parse");
+    assert_eq!(get(&error, "statementIndex"), 1);
+    assert_eq!(get(&error, "line"), 2);
+    assert_eq!(get(&error, "column"), 1);
+}
+
+#[wasm_bindgen_test]
+async fn parse_error_after_escaped_quote() {
+    let mut glue = Glue::new("memory", &JsValue::UNDEFINED).unwrap();
+
+    let sql =
+        "CREATE TABLE Test (name TEXT);\r\nSELECT * FROM Test WHERE name = 'it''s' AN name = 'a';";
+    let error = JsFuture::from(glue.execute(sql.to_owned()))
+        .await
+        .unwrap_err();
+
+    assert_eq!(get(&error, "kind"), "parse");
+    assert_eq!(get(&error, "statementIndex"), 1);
+    assert_eq!(get(&error, "line"), 2);
+    assert_eq!(get(&error, "column"), 41);
+}
+
+#[wasm_bindgen_test]
+async fn execute_error() {
+    let mut glue = Glue::new("memory", &JsValue::UNDEFINED).unwrap();
+
+    let sql = "
+        CREATE TABLE Test (id INTEGER);
+        INSERT INTO Test VALUES (1);
+        SELECT * FROM Nothing;
+    ";
+    let error = JsFuture::from(glue.execute(sql.to_owned()))
+        .await
+        .unwrap_err();
+
+    assert_eq!(get(&error, "kind"), "execute");
+    assert_eq!(get(&error, "statementIndex"), 2);
+    assert!(get(&error, "line").is_undefined());
+    assert_eq!(
+        JSON::stringify(&get(&error, "payloads")).unwrap(),
+        r#"[{"query":"CREATE","data":null},{"query":"INSERT","data":1}]"#
+    );
+
+    let sql = "ALTER TABLE Nothing RENAME TO Something;";
+    let error = JsFuture::from(glue.execute(sql.to_owned()))
+        .await
+        .unwrap_err();
+
+    assert_eq!(get(&error, "kind"), "alter-table");
+    assert_eq!(get(&error, "statementIndex"), 0);
+
+    let error = JsFuture::from(glue.commit()).await.unwrap_err();
+
+    assert_eq!(get(&error, "kind"), "storage");
+    assert_eq!(get(&error, "message"), "no transaction in progress");
+}
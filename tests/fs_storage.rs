@@ -0,0 +1,59 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gluesql::web_storage::{FsKey, FsStorage};
+use gluesql_core::tests::*;
+use gluesql_core::*;
+use js_sys::{Function, Object, Reflect};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+
+use wasm_bindgen_test::*;
+
+// runs in Node.js, the only place with the `fs` module
+
+/// Removes what an earlier run left in `path`, as every test creates its tables again.
+fn remove_dir(path: &str) {
+    let process = Reflect::get(&js_sys::global(), &JsValue::from_str("process")).unwrap();
+    let get_builtin_module: Function =
+        Reflect::get(&process, &JsValue::from_str("getBuiltinModule"))
+            .unwrap()
+            .unchecked_into();
+    let fs = get_builtin_module
+        .call1(&JsValue::NULL, &JsValue::from_str("fs"))
+        .unwrap();
+    let rm_sync: Function = Reflect::get(&fs, &JsValue::from_str("rmSync"))
+        .unwrap()
+        .unchecked_into();
+
+    let options = Object::new();
+    Reflect::set(&options, &JsValue::from_str("recursive"), &JsValue::TRUE).unwrap();
+    Reflect::set(&options, &JsValue::from_str("force"), &JsValue::TRUE).unwrap();
+    rm_sync
+        .call2(&fs, &JsValue::from_str(path), &options)
+        .unwrap();
+}
+
+struct FsTester {
+    storage: Rc<RefCell<Option<FsStorage>>>,
+}
+
+impl Tester<FsKey, FsStorage> for FsTester {
+    fn new(namespace: &str) -> Self {
+        let path = format!("target/fs-storage/{}", namespace);
+        remove_dir(&path);
+
+        let storage = FsStorage::new(path).unwrap_or_else(|_| {
+            panic!("FsStorage::new {}", namespace);
+        });
+        let storage = Rc::new(RefCell::new(Some(storage)));
+
+        Self { storage }
+    }
+
+    fn get_cell(&mut self) -> Rc<RefCell<Option<FsStorage>>> {
+        Rc::clone(&self.storage)
+    }
+}
+
+generate_tests!(wasm_bindgen_test, FsTester);
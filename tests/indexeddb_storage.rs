@@ -0,0 +1,174 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gluesql::web_storage::{IndexedDbKey, IndexedDbStorage};
+use gluesql::Glue;
+use gluesql_core::tests::*;
+use gluesql_core::*;
+use js_sys::{Function, Promise, Reflect, JSON};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+struct IndexedDbTester {
+    storage: Rc<RefCell<Option<IndexedDbStorage>>>,
+}
+
+impl Tester<IndexedDbKey, IndexedDbStorage> for IndexedDbTester {
+    fn new(namespace: &str) -> Self {
+        let storage = IndexedDbStorage::new(namespace.to_string()).unwrap_or_else(|_| {
+            panic!("IndexedDbStorage::new {}", namespace);
+        });
+        let storage = Rc::new(RefCell::new(Some(storage)));
+
+        Self { storage }
+    }
+
+    fn get_cell(&mut self) -> Rc<RefCell<Option<IndexedDbStorage>>> {
+        Rc::clone(&self.storage)
+    }
+}
+
+generate_tests!(wasm_bindgen_test, IndexedDbTester);
+
+#[wasm_bindgen_test]
+async fn indexeddb_version_change() {
+    let mut glue = Glue::new("indexeddb", &JsValue::from_str("version-change")).unwrap();
+
+    let sql = "CREATE TABLE Item (id INTEGER);";
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    // fails unless the open connection closes itself when asked to
+    let body = "
+        return new Promise((resolve, reject) => {
+            const request = indexedDB.deleteDatabase('__gluesql-v0.2__');
+            request.onsuccess = resolve;
+            request.onerror = () => reject(request.error);
+            request.onblocked = () => reject(new Error('blocked'));
+        });
+    ";
+    let deleted: Promise = Function::new_no_args(body)
+        .call0(&JsValue::NULL)
+        .unwrap()
+        .unchecked_into();
+    JsFuture::from(deleted).await.unwrap();
+
+    // the connection is opened again, on a database without the table
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+}
+
+/// Replaces `IDBObjectStore.prototype.put` by `replacement` for values which contain `marker`,
+/// and returns a function which restores it.
+fn patch_put(marker: &str, replacement: &str) -> Function {
+    let body = format!(
+        "
+        const put = IDBObjectStore.prototype.put;
+        IDBObjectStore.prototype.put = function (value, key) {{
+            if (!value.includes(marker)) {{
+                return put.call(this, value, key);
+            }}
+
+            {}
+        }};
+
+        return () => {{ IDBObjectStore.prototype.put = put; }};
+        ",
+        replacement
+    );
+
+    Function::new_with_args("marker", &body)
+        .call1(&JsValue::NULL, &JsValue::from_str(marker))
+        .unwrap()
+        .unchecked_into()
+}
+
+async fn select(glue: &mut Glue) -> String {
+    let result = JsFuture::from(glue.execute("SELECT * FROM Item;".to_owned()))
+        .await
+        .unwrap();
+    let data = Reflect::get(&result, &0.into())
+        .and_then(|payload| Reflect::get(&payload, &JsValue::from_str("data")))
+        .unwrap();
+
+    JSON::stringify(&data).unwrap().as_string().unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn indexeddb_failed_request() {
+    let mut glue = Glue::new("indexeddb", &JsValue::from_str("failed-request")).unwrap();
+
+    let sql = "
+        DROP TABLE IF EXISTS Item;
+        CREATE TABLE Item (id INTEGER, name TEXT);
+        INSERT INTO Item VALUES (1, \"Glue\");
+    ";
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    // adding a key which exists fails the request, after the insert shared its transaction
+    let restore = patch_put("Broken", "return this.add(value, key);");
+    let sql = "
+        INSERT INTO Item VALUES (2, \"Rust\");
+        UPDATE Item SET name = \"Broken\" WHERE id = 1;
+    ";
+    let error = JsFuture::from(glue.execute(sql.to_owned()))
+        .await
+        .unwrap_err();
+    restore.call0(&JsValue::NULL).unwrap();
+
+    assert_eq!(
+        Reflect::get(&error, &JsValue::from_str("statementIndex")).unwrap(),
+        1
+    );
+    let payloads = Reflect::get(&error, &JsValue::from_str("payloads")).unwrap();
+    assert_eq!(
+        JSON::stringify(&payloads).unwrap().as_string().unwrap(),
+        r#"[{"query":"INSERT","data":1}]"#
+    );
+
+    // the insert reported as done is kept
+    assert_eq!(select(&mut glue).await, r#"[[1,"Glue"],[2,"Rust"]]"#);
+}
+
+#[wasm_bindgen_test]
+async fn indexeddb_abort() {
+    let mut glue = Glue::new("indexeddb", &JsValue::from_str("abort")).unwrap();
+
+    let sql = "
+        DROP TABLE IF EXISTS Item;
+        CREATE TABLE Item (id INTEGER, name TEXT);
+        INSERT INTO Item VALUES (1, \"Glue\");
+    ";
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    // the request succeeds, and its transaction aborts afterwards as when the quota is exceeded
+    let restore = patch_put(
+        "Aborted",
+        "
+        const request = put.call(this, value, key);
+        request.addEventListener('success', () => this.transaction.abort());
+
+        return request;
+        ",
+    );
+    let sql = "INSERT INTO Item VALUES (2, \"Aborted\");";
+    let error = JsFuture::from(glue.execute(sql.to_owned()))
+        .await
+        .unwrap_err();
+    restore.call0(&JsValue::NULL).unwrap();
+
+    assert_eq!(
+        Reflect::get(&error, &JsValue::from_str("kind")).unwrap(),
+        "storage"
+    );
+    let payloads = Reflect::get(&error, &JsValue::from_str("payloads")).unwrap();
+    assert_eq!(
+        JSON::stringify(&payloads).unwrap().as_string().unwrap(),
+        "[]"
+    );
+
+    assert_eq!(select(&mut glue).await, r#"[[1,"Glue"]]"#);
+}
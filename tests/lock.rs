@@ -0,0 +1,70 @@
+use gluesql::Glue;
+use js_sys::JSON;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+async fn lock() {
+    // two instances of the same database, as two tabs would open it
+    let namespace = JsValue::from_str("lock");
+    let mut first = Glue::new("indexeddb", &namespace).unwrap();
+    let mut second = Glue::new("indexeddb", &namespace).unwrap();
+
+    let sql = "
+        DROP TABLE IF EXISTS Item;
+        CREATE TABLE Item (id INTEGER);
+    ";
+    JsFuture::from(first.execute(sql.to_owned())).await.unwrap();
+
+    let mut calls = vec![];
+    for i in 0..5 {
+        let sql = format!("INSERT INTO Item VALUES ({});", i);
+
+        calls.push(first.execute(sql.clone()));
+        calls.push(second.execute(sql));
+    }
+
+    for call in calls {
+        JsFuture::from(call).await.unwrap();
+    }
+
+    let result = JsFuture::from(first.execute("SELECT COUNT(*) FROM Item;".to_owned()))
+        .await
+        .unwrap();
+    let result = JSON::stringify(&result).unwrap().as_string().unwrap();
+    assert!(result.ends_with(r#""data":[[10]]}]"#), "{}", result);
+}
+
+#[wasm_bindgen_test]
+async fn lock_transaction() {
+    let namespace = JsValue::from_str("lock-transaction");
+    let mut first = Glue::new("indexeddb", &namespace).unwrap();
+    let mut second = Glue::new("indexeddb", &namespace).unwrap();
+
+    let sql = "
+        DROP TABLE IF EXISTS Item;
+        CREATE TABLE Item (id INTEGER);
+    ";
+    JsFuture::from(first.execute(sql.to_owned())).await.unwrap();
+
+    JsFuture::from(first.begin()).await.unwrap();
+    JsFuture::from(first.execute("INSERT INTO Item VALUES (1);".to_owned()))
+        .await
+        .unwrap();
+
+    // waits for the transaction of `first` to end
+    let inserted = second.execute("INSERT INTO Item VALUES (2);".to_owned());
+
+    JsFuture::from(first.commit()).await.unwrap();
+    JsFuture::from(inserted).await.unwrap();
+
+    let result = JsFuture::from(first.execute("SELECT id FROM Item;".to_owned()))
+        .await
+        .unwrap();
+    let result = JSON::stringify(&result).unwrap().as_string().unwrap();
+    assert!(result.ends_with(r#""data":[[1],[2]]}]"#), "{}", result);
+}
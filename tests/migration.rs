@@ -0,0 +1,106 @@
+use gluesql::Glue;
+use js_sys::{Reflect, JSON};
+use std::collections::BTreeMap;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen::JsCast;
+use wasm_bindgen_futures::JsFuture;
+use web_sys::Storage;
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn session_storage() -> Storage {
+    Reflect::get(&js_sys::global(), &JsValue::from_str("sessionStorage"))
+        .unwrap()
+        .unchecked_into()
+}
+
+fn items(prefix: &str) -> BTreeMap<String, String> {
+    let storage = session_storage();
+
+    (0..storage.length().unwrap())
+        .filter_map(|index| storage.key(index).unwrap())
+        .filter(|key| key.starts_with(prefix))
+        .map(|key| {
+            let value = storage.get_item(&key).unwrap().unwrap();
+
+            (key, value)
+        })
+        .collect()
+}
+
+fn item(key: &str) -> String {
+    session_storage().get_item(key).unwrap().unwrap()
+}
+
+async fn select(glue: &mut Glue) -> String {
+    let sql = "SELECT id FROM Item;".to_owned();
+    let result = JsFuture::from(glue.execute(sql)).await.unwrap();
+    let data = Reflect::get(&result, &0.into())
+        .and_then(|payload| Reflect::get(&payload, &JsValue::from_str("data")))
+        .unwrap();
+
+    JSON::stringify(&data).unwrap().as_string().unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn migration() {
+    let storage = session_storage();
+    for key in items("__gluesql-v0.3__/migration/").keys() {
+        storage.remove_item(key).unwrap();
+    }
+
+    // schema and rows are serialized the same way by both layouts
+    let mut source = Glue::new("sessionstorage", &JsValue::from_str("migration-source")).unwrap();
+    let sql = "
+        DROP TABLE IF EXISTS Item;
+        CREATE TABLE Item (id INTEGER);
+        INSERT INTO Item VALUES (1);
+        INSERT INTO Item VALUES (2);
+    ";
+    JsFuture::from(source.execute(sql.to_owned()))
+        .await
+        .unwrap();
+
+    let source_prefix = "__gluesql-v0.3__/migration-source";
+    let schema = item(&format!("{}/schema/Item", source_prefix));
+    let id = item(&format!("{}/id/Item", source_prefix));
+    let data = format!(
+        "[[1,{}],[2,{}]]",
+        item(&format!("{}/data/Item/1", source_prefix)),
+        item(&format!("{}/data/Item/2", source_prefix)),
+    );
+
+    // the v0.2 layout kept every row of a table in a single blob
+    let legacy_prefix = "__gluesql-v0.2__/migration";
+    storage
+        .set_item(&format!("{}/schema/Item", legacy_prefix), &schema)
+        .unwrap();
+    storage
+        .set_item(&format!("{}/id/Item", legacy_prefix), &id)
+        .unwrap();
+    storage
+        .set_item(&format!("{}/data/Item", legacy_prefix), &data)
+        .unwrap();
+
+    let namespace = JsValue::from_str("migration");
+    let mut glue = Glue::new("sessionstorage", &namespace).unwrap();
+    assert_eq!(select(&mut glue).await, "[[1],[2]]");
+
+    let prefix = "__gluesql-v0.3__/migration";
+    assert_eq!(item(&format!("{}/index/Item", prefix)), "[1,2]");
+    assert_eq!(item(&format!("{}/id/Item", prefix)), id);
+    assert!(items(&format!("{}/", legacy_prefix)).is_empty());
+
+    // a row inserted after the migration gets the next id instead of overwriting one
+    JsFuture::from(glue.execute("INSERT INTO Item VALUES (3);".to_owned()))
+        .await
+        .unwrap();
+    assert_eq!(select(&mut glue).await, "[[1],[2],[3]]");
+
+    let migrated = items(&format!("{}/", prefix));
+    let mut glue = Glue::new("sessionstorage", &namespace).unwrap();
+    assert_eq!(select(&mut glue).await, "[[1],[2],[3]]");
+    assert_eq!(items(&format!("{}/", prefix)), migrated);
+}
@@ -0,0 +1,70 @@
+use gluesql::Glue;
+use js_sys::{Array, Function, Reflect, JSON};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+/// Fires the `storage` event another tab would fire when writing `key`.
+fn dispatch(key: &str, old_value: &JsValue, new_value: &JsValue) {
+    let body = "
+        window.dispatchEvent(new StorageEvent('storage', {
+            key, oldValue, newValue, storageArea: localStorage,
+        }));
+    ";
+
+    Function::new_with_args("key, oldValue, newValue", body)
+        .call3(
+            &JsValue::NULL,
+            &JsValue::from_str(key),
+            old_value,
+            new_value,
+        )
+        .unwrap();
+}
+
+#[wasm_bindgen_test]
+async fn on_change() {
+    let glue = Glue::new("localstorage", &JsValue::from_str("on-change")).unwrap();
+
+    let changes = Array::new();
+    let push = Function::new_with_args("changes", "return (change) => changes.push(change);")
+        .call1(&JsValue::NULL, &changes)
+        .unwrap();
+
+    let subscription = JsFuture::from(glue.on_change(push.into())).await.unwrap();
+
+    let key = |suffix: &str| format!("__gluesql-v0.3__/on-change/{}", suffix);
+    let value = JsValue::from_str("{}");
+
+    dispatch(&key("schema/Item"), &JsValue::NULL, &value);
+    dispatch(&key("data/Item/1"), &JsValue::NULL, &value);
+    dispatch(&key("data/Item/1"), &value, &value);
+    dispatch(&key("data/Item/1"), &value, &JsValue::NULL);
+    dispatch(&key("index/Item"), &JsValue::NULL, &value);
+    dispatch("__gluesql-v0.3__/other/data/Item/1", &JsValue::NULL, &value);
+
+    let unsubscribe: Function = Reflect::get(&subscription, &JsValue::from_str("unsubscribe"))
+        .unwrap()
+        .into();
+    unsubscribe.call0(&subscription).unwrap();
+    dispatch(&key("schema/Item"), &value, &JsValue::NULL);
+
+    assert_eq!(
+        JSON::stringify(&changes).unwrap().as_string().unwrap(),
+        concat!(
+            r#"[{"table":"Item","kind":"create"},{"table":"Item","kind":"insert"},"#,
+            r#"{"table":"Item","kind":"update"},{"table":"Item","kind":"delete"}]"#,
+        )
+    );
+
+    let memory = Glue::new("memory", &JsValue::UNDEFINED).unwrap();
+    let noop = Function::new_no_args("");
+    let error = JsFuture::from(memory.on_change(noop)).await.unwrap_err();
+    assert_eq!(
+        Reflect::get(&error, &JsValue::from_str("kind")).unwrap(),
+        "storage"
+    );
+}
@@ -0,0 +1,31 @@
+use std::cell::RefCell;
+use std::rc::Rc;
+
+use gluesql::web_storage::{OpfsKey, OpfsStorage};
+use gluesql_core::tests::*;
+use gluesql_core::*;
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+struct OpfsTester {
+    storage: Rc<RefCell<Option<OpfsStorage>>>,
+}
+
+impl Tester<OpfsKey, OpfsStorage> for OpfsTester {
+    fn new(namespace: &str) -> Self {
+        let storage = OpfsStorage::new(namespace.to_string()).unwrap_or_else(|_| {
+            panic!("OpfsStorage::new {}", namespace);
+        });
+        let storage = Rc::new(RefCell::new(Some(storage)));
+
+        Self { storage }
+    }
+
+    fn get_cell(&mut self) -> Rc<RefCell<Option<OpfsStorage>>> {
+        Rc::clone(&self.storage)
+    }
+}
+
+generate_tests!(wasm_bindgen_test, OpfsTester);
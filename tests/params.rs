@@ -0,0 +1,104 @@
+use gluesql::Glue;
+use js_sys::{Array, Object, Reflect, JSON};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn stringify(value: &JsValue) -> String {
+    JSON::stringify(value).unwrap().as_string().unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn params() {
+    let mut glue = Glue::new("memory", &JsValue::UNDEFINED).unwrap();
+
+    let sql = "CREATE TABLE Test (id INTEGER, name TEXT NULL, flag BOOLEAN);";
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    let params = Array::of3(&1.into(), &"it's \"quoted\"".into(), &true.into());
+    let sql = "INSERT INTO Test VALUES (?, ?, ?);";
+    JsFuture::from(glue.execute_with_params(sql.to_owned(), params))
+        .await
+        .unwrap();
+
+    let params = Array::of3(&false.into(), &JsValue::NULL, &2.into());
+    let sql = "INSERT INTO Test VALUES ($3, $2, $1);";
+    JsFuture::from(glue.execute_with_params(sql.to_owned(), params))
+        .await
+        .unwrap();
+
+    let params = Object::new();
+    Reflect::set(&params, &"id".into(), &2.into()).unwrap();
+    let sql = "SELECT * FROM Test WHERE id = $id;";
+    let result = JsFuture::from(glue.execute_with_named_params(sql.to_owned(), params))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        stringify(&result),
+        r#"[{"query":"SELECT","labels":["id","name","flag"],"types":["INT","TEXT","BOOLEAN"],"data":[[2,null,false]]}]"#
+    );
+
+    let sql = "SELECT * FROM Test WHERE id = ?;";
+    let result = JsFuture::from(glue.execute_with_params(sql.to_owned(), Array::of1(&1.into())))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        stringify(&result),
+        r#"[{"query":"SELECT","labels":["id","name","flag"],"types":["INT","TEXT","BOOLEAN"],"data":[[1,"it's \"quoted\"",true]]}]"#
+    );
+
+    let sql = "SELECT * FROM Test WHERE id = ?;";
+    let result = JsFuture::from(glue.execute_with_params(sql.to_owned(), Array::new())).await;
+
+    assert!(result.is_err());
+}
+
+#[wasm_bindgen_test]
+async fn prepare() {
+    let mut glue = Glue::new("memory", &JsValue::UNDEFINED).unwrap();
+
+    let sql = "CREATE TABLE Test (id INTEGER, name TEXT);";
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    let insert = glue
+        .prepare("INSERT INTO Test VALUES (?, ?);".to_owned())
+        .unwrap();
+
+    for (id, name) in [(1, "Glue"), (2, "SQL")].iter() {
+        let params = Array::of2(&(*id).into(), &(*name).into());
+        JsFuture::from(insert.execute(params.into())).await.unwrap();
+    }
+
+    let select = glue
+        .prepare("SELECT name FROM Test WHERE id = $id;".to_owned())
+        .unwrap();
+
+    let params = Object::new();
+    Reflect::set(&params, &"id".into(), &2.into()).unwrap();
+    let result = JsFuture::from(select.execute(params.into())).await.unwrap();
+
+    assert_eq!(
+        stringify(&result),
+        r#"[{"query":"SELECT","labels":["name"],"types":["TEXT"],"data":[["SQL"]]}]"#
+    );
+
+    let count = glue.prepare("SELECT * FROM Test;".to_owned()).unwrap();
+    let result = JsFuture::from(count.execute(JsValue::UNDEFINED))
+        .await
+        .unwrap();
+
+    assert_eq!(
+        stringify(&result),
+        r#"[{"query":"SELECT","labels":["id","name"],"types":["INT","TEXT"],"data":[[1,"Glue"],[2,"SQL"]]}]"#
+    );
+
+    let result = JsFuture::from(select.execute(Array::new().into())).await;
+    assert!(result.is_err());
+
+    assert!(glue.prepare("SELEC * FROM Test;".to_owned()).is_err());
+}
@@ -0,0 +1,38 @@
+use gluesql::Glue;
+use js_sys::JSON;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+async fn overlapping_calls() {
+    let namespace = JsValue::from_str("queue");
+    let mut glue = Glue::new("sessionstorage", &namespace).unwrap();
+
+    let calls = vec![
+        glue.execute("DROP TABLE IF EXISTS Item;".to_owned()),
+        glue.execute("CREATE TABLE Item (id INTEGER);".to_owned()),
+        glue.execute("INSERT INTO Item VALUES (1);".to_owned()),
+        glue.execute("INSERT INTO Missing VALUES (1);".to_owned()),
+        glue.execute("INSERT INTO Item VALUES (2);".to_owned()),
+        glue.tables(),
+        glue.execute("SELECT * FROM Item;".to_owned()),
+    ];
+
+    let mut results = vec![];
+    for call in calls {
+        results.push(JsFuture::from(call).await);
+    }
+
+    assert!(results[3].is_err());
+
+    let stringify = |value: &JsValue| JSON::stringify(value).unwrap().as_string().unwrap();
+    let selected = results.pop().unwrap().unwrap();
+    assert_eq!(
+        stringify(&selected),
+        r#"[{"query":"SELECT","labels":["id"],"types":["INT"],"data":[[1],[2]]}]"#
+    );
+}
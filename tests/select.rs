@@ -0,0 +1,57 @@
+use gluesql::Glue;
+use js_sys::JSON;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn stringify(value: &JsValue) -> String {
+    JSON::stringify(value).unwrap().as_string().unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn select_labels() {
+    let mut glue = Glue::new("memory", &JsValue::UNDEFINED).unwrap();
+
+    let sql = "
+        CREATE TABLE Item (id INTEGER, name TEXT);
+        INSERT INTO Item VALUES (1, \"Glue\");
+    ";
+    let result = JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    assert_eq!(
+        stringify(&result),
+        r#"[{"query":"CREATE","data":null},{"query":"INSERT","data":1}]"#
+    );
+
+    let sql = "SELECT id + 1, name AS title FROM Item;";
+    let result = JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    assert_eq!(
+        stringify(&result),
+        r#"[{"query":"SELECT","labels":["id + 1","title"],"types":[null,"TEXT"],"data":[[2,"Glue"]]}]"#
+    );
+}
+
+#[wasm_bindgen_test]
+async fn object_rows() {
+    let mut glue = Glue::new("memory", &JsValue::UNDEFINED).unwrap();
+    glue.set_object_rows(true);
+
+    let sql = "
+        CREATE TABLE Item (id INTEGER, name TEXT);
+        INSERT INTO Item VALUES (101, \"Glue\");
+        INSERT INTO Item VALUES (102, \"Rust\");
+    ";
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    let sql = "SELECT * FROM Item;";
+    let result = JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    assert_eq!(
+        stringify(&result),
+        r#"[{"query":"SELECT","labels":["id","name"],"types":["INT","TEXT"],"data":[{"id":101,"name":"Glue"},{"id":102,"name":"Rust"}]}]"#
+    );
+}
@@ -0,0 +1,51 @@
+use gluesql::Glue;
+use js_sys::{Reflect, Uint8Array};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+async fn snapshot() {
+    let mut glue = Glue::new("memory", &JsValue::UNDEFINED).unwrap();
+
+    let sql = "
+        CREATE TABLE Item (id INTEGER, name TEXT NULL);
+        INSERT INTO Item VALUES (1, \"Glue\"), (2, NULL);
+    ";
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    let snapshot = JsFuture::from(glue.snapshot()).await.unwrap();
+    let snapshot = Uint8Array::from(snapshot);
+
+    let mut restored = Glue::from_snapshot(&snapshot).unwrap();
+    let dumped = JsFuture::from(glue.dump()).await.unwrap();
+    let restored_dumped = JsFuture::from(restored.dump()).await.unwrap();
+    assert_eq!(restored_dumped.as_string(), dumped.as_string());
+
+    JsFuture::from(restored.execute("INSERT INTO Item VALUES (3, \"Rust\");".to_owned()))
+        .await
+        .unwrap();
+    let result = JsFuture::from(restored.execute("SELECT id FROM Item;".to_owned()))
+        .await
+        .unwrap();
+    assert_eq!(
+        js_sys::JSON::stringify(&result)
+            .unwrap()
+            .as_string()
+            .unwrap(),
+        r#"[{"query":"SELECT","labels":["id"],"types":["INT"],"data":[[1],[2],[3]]}]"#
+    );
+
+    let invalid = Uint8Array::from(&[1u8, 0][..]);
+    assert!(Glue::from_snapshot(&invalid).is_err());
+
+    let glue = Glue::new("sessionstorage", &JsValue::from_str("snapshot")).unwrap();
+    let error = JsFuture::from(glue.snapshot()).await.unwrap_err();
+    assert_eq!(
+        Reflect::get(&error, &JsValue::from_str("kind")).unwrap(),
+        "storage"
+    );
+}
@@ -0,0 +1,101 @@
+use gluesql::Glue;
+use js_sys::{Array, Function, Reflect, JSON};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn get(value: &JsValue, key: &str) -> JsValue {
+    Reflect::get(value, &JsValue::from_str(key)).unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn storage_usage() {
+    let mut glue = Glue::new("sessionstorage", &JsValue::from_str("storage-usage")).unwrap();
+
+    let sql = "
+        DROP TABLE IF EXISTS Item;
+        CREATE TABLE Item (id INTEGER, name TEXT);
+        INSERT INTO Item VALUES (1, \"Glue\");
+    ";
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    let usage = JsFuture::from(glue.storage_usage()).await.unwrap();
+    let usage = Array::from(&usage);
+    assert_eq!(usage.length(), 1);
+    assert_eq!(get(&usage.get(0), "table").as_string().unwrap(), "Item");
+
+    let bytes = get(&usage.get(0), "bytes").as_f64().unwrap();
+    JsFuture::from(glue.execute("INSERT INTO Item VALUES (2, \"Rust\");".to_owned()))
+        .await
+        .unwrap();
+    let usage = Array::from(&JsFuture::from(glue.storage_usage()).await.unwrap());
+    assert!(get(&usage.get(0), "bytes").as_f64().unwrap() > bytes);
+
+    let memory = Glue::new("memory", &JsValue::UNDEFINED).unwrap();
+    assert!(JsFuture::from(memory.storage_usage()).await.is_err());
+}
+
+#[wasm_bindgen_test]
+async fn quota_exceeded() {
+    for name in ["QuotaExceededError", "NS_ERROR_DOM_QUOTA_REACHED"].iter() {
+        let body = "
+            return {
+                get: async () => null,
+                set: async () => { throw new DOMException('storage is full', name); },
+                remove: async () => {},
+                keys: async () => [],
+            };
+        ";
+        let storage = Function::new_with_args("name", body)
+            .call1(&JsValue::NULL, &JsValue::from_str(name))
+            .unwrap();
+        let mut glue = Glue::with_custom_storage(storage, None).unwrap();
+
+        let error = JsFuture::from(glue.execute("CREATE TABLE Item (id INTEGER);".to_owned()))
+            .await
+            .unwrap_err();
+
+        assert_eq!(get(&error, "kind").as_string().unwrap(), "quota-exceeded");
+    }
+}
+
+async fn web_quota_exceeded(storage_type: &str) {
+    let mut glue = Glue::new(storage_type, &JsValue::from_str("quota-exceeded")).unwrap();
+    glue.set_atomic(true);
+
+    let sql = "
+        DROP TABLE IF EXISTS Item;
+        CREATE TABLE Item (name TEXT);
+    ";
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    // larger than the quota of localStorage and sessionStorage in every browser
+    let name = JsValue::from_str(&"x".repeat(12 * 1024 * 1024));
+    let sql = "INSERT INTO Item VALUES (?);".to_owned();
+    let error = JsFuture::from(glue.execute_with_params(sql, Array::of1(&name)))
+        .await
+        .unwrap_err();
+
+    assert_eq!(get(&error, "kind").as_string().unwrap(), "quota-exceeded");
+
+    let result = JsFuture::from(glue.execute("SELECT * FROM Item;".to_owned()))
+        .await
+        .unwrap();
+    assert_eq!(
+        JSON::stringify(&get(&Array::from(&result).get(0), "data")).unwrap(),
+        "[]"
+    );
+}
+
+#[wasm_bindgen_test]
+async fn local_quota_exceeded() {
+    web_quota_exceeded("localstorage").await;
+}
+
+#[wasm_bindgen_test]
+async fn session_quota_exceeded() {
+    web_quota_exceeded("sessionstorage").await;
+}
@@ -0,0 +1,131 @@
+use gluesql::Glue;
+use js_sys::{Array, Function, Reflect, JSON};
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+#[wasm_bindgen_test]
+async fn subscribe() {
+    let mut glue = Glue::new("memory", &JsValue::UNDEFINED).unwrap();
+
+    let sql = "
+        CREATE TABLE Item (id INTEGER);
+        CREATE TABLE Other (id INTEGER);
+        INSERT INTO Item VALUES (1);
+    ";
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    let results = Array::new();
+    let push = Function::new_with_args("results", "return (result) => results.push(result);")
+        .call1(&JsValue::NULL, &results)
+        .unwrap();
+
+    let subscription = glue.subscribe("SELECT id FROM Item WHERE id > 1;".to_owned(), push.into());
+    let subscription = JsFuture::from(subscription).await.unwrap();
+
+    for sql in [
+        "INSERT INTO Item VALUES (2);",
+        "INSERT INTO Other VALUES (3);",
+        "DELETE FROM Item WHERE id = 1;",
+    ]
+    .iter()
+    {
+        JsFuture::from(glue.execute(sql.to_string())).await.unwrap();
+    }
+
+    let unsubscribe: Function = Reflect::get(&subscription, &JsValue::from_str("unsubscribe"))
+        .unwrap()
+        .into();
+    unsubscribe.call0(&subscription).unwrap();
+
+    JsFuture::from(glue.execute("INSERT INTO Item VALUES (4);".to_owned()))
+        .await
+        .unwrap();
+
+    let data = results
+        .iter()
+        .map(|result| Reflect::get(&result, &JsValue::from_str("data")).unwrap())
+        .collect::<Array>();
+    assert_eq!(
+        JSON::stringify(&data).unwrap().as_string().unwrap(),
+        "[[],[[2]],[[2]]]"
+    );
+
+    let noop = Function::new_no_args("");
+    let insert = glue.subscribe("INSERT INTO Item VALUES (5);".to_owned(), noop);
+    assert!(JsFuture::from(insert).await.is_err());
+}
+
+#[wasm_bindgen_test]
+async fn subscribe_subqueries() {
+    let mut glue = Glue::new("memory", &JsValue::UNDEFINED).unwrap();
+
+    let sql = "
+        CREATE TABLE Item (id INTEGER);
+        CREATE TABLE Other (id INTEGER);
+        CREATE TABLE Picked (id INTEGER);
+        CREATE TABLE Label (id INTEGER);
+        INSERT INTO Item VALUES (1);
+        INSERT INTO Other VALUES (1);
+        INSERT INTO Label VALUES (7);
+    ";
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    let results = Array::new();
+    let push = Function::new_with_args("results", "return (result) => results.push(result);")
+        .call1(&JsValue::NULL, &results)
+        .unwrap();
+
+    let sql = "
+        SELECT Item.id, (SELECT id FROM Label) AS label
+        FROM Item
+        JOIN Other ON Other.id = Item.id AND Item.id IN (SELECT id FROM Picked);
+    ";
+    let subscription = glue.subscribe(sql.to_owned(), push.into());
+    JsFuture::from(subscription).await.unwrap();
+
+    for sql in ["INSERT INTO Picked VALUES (1);", "UPDATE Label SET id = 8;"].iter() {
+        JsFuture::from(glue.execute(sql.to_string())).await.unwrap();
+    }
+
+    let data = results
+        .iter()
+        .map(|result| Reflect::get(&result, &JsValue::from_str("data")).unwrap())
+        .collect::<Array>();
+    assert_eq!(
+        JSON::stringify(&data).unwrap().as_string().unwrap(),
+        "[[],[[1,7]],[[1,8]]]"
+    );
+}
+
+#[wasm_bindgen_test]
+async fn subscribe_free() {
+    let mut glue = Glue::new("memory", &JsValue::UNDEFINED).unwrap();
+
+    JsFuture::from(glue.execute("CREATE TABLE Item (id INTEGER);".to_owned()))
+        .await
+        .unwrap();
+
+    let results = Array::new();
+    let push = Function::new_with_args("results", "return (result) => results.push(result);")
+        .call1(&JsValue::NULL, &results)
+        .unwrap();
+
+    let subscription = glue.subscribe("SELECT id FROM Item;".to_owned(), push.into());
+    let subscription = JsFuture::from(subscription).await.unwrap();
+
+    // the garbage collector frees handles nobody keeps, which must not stop the live query
+    let free: Function = Reflect::get(&subscription, &JsValue::from_str("free"))
+        .unwrap()
+        .into();
+    free.call0(&subscription).unwrap();
+
+    JsFuture::from(glue.execute("INSERT INTO Item VALUES (1);".to_owned()))
+        .await
+        .unwrap();
+
+    assert_eq!(results.length(), 2);
+}
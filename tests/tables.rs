@@ -0,0 +1,44 @@
+use gluesql::Glue;
+use js_sys::JSON;
+use wasm_bindgen::prelude::*;
+use wasm_bindgen_futures::JsFuture;
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+fn stringify(value: &JsValue) -> String {
+    JSON::stringify(value).unwrap().as_string().unwrap()
+}
+
+#[wasm_bindgen_test]
+async fn tables() {
+    let mut glue = Glue::new("memory", &JsValue::UNDEFINED).unwrap();
+
+    let sql = "
+        CREATE TABLE Item (id INTEGER, name TEXT NULL, price FLOAT DEFAULT 1.5);
+        CREATE TABLE Flag (active BOOLEAN);
+    ";
+    JsFuture::from(glue.execute(sql.to_owned())).await.unwrap();
+
+    let tables = JsFuture::from(glue.tables()).await.unwrap();
+    assert_eq!(
+        stringify(&tables),
+        concat!(
+            r#"[{"name":"Flag","columns":["#,
+            r#"{"name":"active","type":"BOOLEAN","nullable":false,"default":null}]},"#,
+            r#"{"name":"Item","columns":["#,
+            r#"{"name":"id","type":"INT","nullable":false,"default":null},"#,
+            r#"{"name":"name","type":"TEXT","nullable":true,"default":null},"#,
+            r#"{"name":"price","type":"FLOAT","nullable":false,"default":"1.5"}]}]"#,
+        )
+    );
+
+    let result = JsFuture::from(glue.execute("SHOW TABLES;".to_owned()))
+        .await
+        .unwrap();
+    assert_eq!(
+        stringify(&result),
+        r#"[{"query":"SHOW TABLES","data":["Flag","Item"]}]"#
+    );
+}
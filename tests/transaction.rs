@@ -0,0 +1,74 @@
+use std::fmt::Debug;
+
+use gluesql::memory_storage::MemoryStorage;
+use gluesql::transaction::Transaction;
+use gluesql::web_storage::LocalStorage;
+use gluesql_core::*;
+
+use wasm_bindgen_test::*;
+
+wasm_bindgen_test_configure!(run_in_browser);
+
+async fn run<T, U>(storage: U, sql: &str) -> (U, Payload)
+where
+    T: 'static + Debug,
+    U: Store<T> + StoreMut<T> + AlterTable,
+{
+    let query = &parse(sql).unwrap()[0];
+
+    match execute(storage, query).await {
+        Ok(result) => result,
+        Err((_, error)) => panic!("{}: {:?}", sql, error),
+    }
+}
+
+async fn count<T, U>(storage: U) -> (U, usize)
+where
+    T: 'static + Debug,
+    U: Store<T> + StoreMut<T> + AlterTable,
+{
+    match run(storage, "SELECT * FROM Test;").await {
+        (storage, Payload::Select { rows, .. }) => (storage, rows.len()),
+        (_, payload) => panic!("unexpected payload: {:?}", payload),
+    }
+}
+
+async fn test<T, U>(storage: U)
+where
+    T: 'static + Debug,
+    U: Store<T> + StoreMut<T> + AlterTable + Transaction,
+{
+    let (storage, _) = run(storage, "DROP TABLE IF EXISTS Test;").await;
+    let (storage, _) = run(storage, "CREATE TABLE Test (id INTEGER);").await;
+    let (storage, _) = run(storage, "INSERT INTO Test VALUES (1);").await;
+
+    let (storage, _) = storage.begin().await.unwrap_or_else(|_| panic!("begin"));
+    let (storage, _) = run(storage, "INSERT INTO Test VALUES (2);").await;
+    let (storage, num) = count(storage).await;
+    assert_eq!(num, 2);
+
+    let (storage, _) = storage
+        .rollback()
+        .await
+        .unwrap_or_else(|_| panic!("rollback"));
+    let (storage, num) = count(storage).await;
+    assert_eq!(num, 1);
+
+    let (storage, _) = storage.begin().await.unwrap_or_else(|_| panic!("begin"));
+    let (storage, _) = run(storage, "DELETE FROM Test;").await;
+    let (storage, _) = storage.commit().await.unwrap_or_else(|_| panic!("commit"));
+    let (storage, num) = count(storage).await;
+    assert_eq!(num, 0);
+
+    assert!(storage.commit().await.is_err());
+}
+
+#[wasm_bindgen_test]
+async fn memory_transaction() {
+    test(MemoryStorage::new().unwrap()).await;
+}
+
+#[wasm_bindgen_test]
+async fn local_transaction() {
+    test(LocalStorage::new("transaction".to_owned()).unwrap()).await;
+}